@@ -1,5 +1,8 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Expr, FnArg, ItemFn, Pat, ReturnType, Token, Type, parse_macro_input};
 
 // Again, huge thanks to andi_makes
 // this is basically a copy of his macros, with very slight modifications.
@@ -85,7 +88,7 @@ pub fn match_and_test_day_one(_input: TokenStream) -> TokenStream {
     let res = quote! {
         match day {
             #(#r => {
-                Day::<#r>::test_one()
+                Day::<#r>::test_one(example)
             })*
             _ => panic!("Days out of Bounds! No presents for you!"),
         }
@@ -99,10 +102,243 @@ pub fn match_and_test_day_two(_input: TokenStream) -> TokenStream {
     let res = quote! {
         match day {
             #(#r => {
-                Day::<#r>::test_two()
+                Day::<#r>::test_two(example)
             })*
             _ => panic!("Days out of Bounds! No presents for you!"),
         }
     };
     res.into()
 }
+
+#[proc_macro]
+pub fn match_and_profile_day(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::profile(input.trim_end(), phase, limit)
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_crosscheck_day(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                let (solution, data) = match input {
+                    Some(input) => Day::<#r>::init(input.trim_end()),
+                    None => Day::<#r>::init_test(),
+                };
+                solution.crosscheck(&data)
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_visualize_day(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                let (solution, mut data) = match input {
+                    Some(input) => Day::<#r>::init(input),
+                    None => Day::<#r>::init_test(),
+                };
+                solution.solve(&mut data, |solution, data| solution.visualize(data, emit))
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_generate_day(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::generate(&mut rng, size)
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn match_and_validate_day(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        match day {
+            #(#r => {
+                Day::<#r>::validate(input.trim_end())
+            })*
+            _ => panic!("Days out of Bounds! No presents for you!"),
+        }
+    };
+    res.into()
+}
+
+#[proc_macro]
+pub fn bench_days(_input: TokenStream) -> TokenStream {
+    let r = 1_u8..26; // == [1,25]
+    let res = quote! {
+        #(bench_day::<Day<#r>, _>(#r, c);)*
+    };
+    res.into()
+}
+
+/// The optional `key: Type = expression` argument of `#[memoize]`.
+struct MemoizeKey {
+    ty: Type,
+    expr: Expr,
+}
+
+impl Parse for MemoizeKey {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: syn::Ident = input.parse()?;
+        if name != "key" {
+            return Err(syn::Error::new(
+                name.span(),
+                "expected `key: Type = expression`",
+            ));
+        }
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let expr = input.parse()?;
+
+        Ok(Self { ty, expr })
+    }
+}
+
+/// Caches the results of a pure function, keyed on its arguments.
+///
+/// By default, the key is a tuple of all (owned) arguments. Functions taking references, like
+/// slices, need a projection to an owned key instead, e.g.
+/// `#[memoize(key: (Vec<u8>, u32) = (sequence.to_vec(), num_digits))]`.
+///
+/// The cache is local to the thread and only holds results of the current scope,
+/// see `DayImpl::solve`. Generic functions and methods are not supported.
+///
+/// The expansion refers to `crate::memo::MemoCache`, so the attribute can only be used inside
+/// the `aoc25` crate, which provides that module.
+#[proc_macro_attribute]
+pub fn memoize(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let key = match attr.is_empty() {
+        true => None,
+        false => Some(parse_macro_input!(attr as MemoizeKey)),
+    };
+    let function = parse_macro_input!(item as ItemFn);
+
+    match expand_memoize(key, function) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand_memoize(
+    key: Option<MemoizeKey>,
+    function: ItemFn,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = function;
+
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "memoized functions can't be generic",
+        ));
+    }
+    if let Some(asyncness) = sig.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "memoized functions can't be async",
+        ));
+    }
+    let output = match &sig.output {
+        ReturnType::Type(_, ty) => ty.as_ref().clone(),
+        ReturnType::Default => {
+            return Err(syn::Error::new(
+                sig.span(),
+                "memoized functions need to return a value",
+            ));
+        }
+    };
+
+    let mut names = Vec::with_capacity(sig.inputs.len());
+    let mut types = Vec::with_capacity(sig.inputs.len());
+    let mut outer_sig = sig.clone();
+    for input in outer_sig.inputs.iter_mut() {
+        let FnArg::Typed(argument) = input else {
+            return Err(syn::Error::new(
+                input.span(),
+                "memoized functions can't take `self`",
+            ));
+        };
+        let Pat::Ident(pattern) = argument.pat.as_mut() else {
+            return Err(syn::Error::new(
+                argument.pat.span(),
+                "arguments of memoized functions need to be plain identifiers",
+            ));
+        };
+
+        // The arguments are only passed on, so they don't need to be mutable here.
+        pattern.mutability = None;
+        names.push(pattern.ident.clone());
+        types.push(argument.ty.as_ref().clone());
+    }
+
+    let (key_type, key_expr) = match key {
+        Some(MemoizeKey { ty, expr }) => (quote! { #ty }, quote! { #expr }),
+        None => {
+            if let Some(reference) = types.iter().find(|ty| matches!(ty, Type::Reference(_))) {
+                return Err(syn::Error::new(
+                    reference.span(),
+                    "borrowed arguments need a key projection, e.g. `#[memoize(key: Vec<u8> = values.to_vec())]`",
+                ));
+            }
+            (
+                quote! { (#(#types,)*) },
+                quote! { (#(::std::clone::Clone::clone(&#names),)*) },
+            )
+        }
+    };
+
+    let mut inner_sig = sig;
+    inner_sig.ident = syn::Ident::new("uncached", inner_sig.ident.span());
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #outer_sig {
+            #inner_sig #block
+
+            ::std::thread_local! {
+                static CACHE: ::std::cell::RefCell<crate::memo::MemoCache<#key_type, #output>> =
+                    ::std::cell::RefCell::new(crate::memo::MemoCache::new());
+            }
+
+            let key: #key_type = #key_expr;
+            if let Some(result) = CACHE.with_borrow_mut(|cache| cache.get(&key)) {
+                return result;
+            }
+
+            let result = uncached(#(#names),*);
+            CACHE.with_borrow_mut(|cache| cache.insert(key, ::std::clone::Clone::clone(&result)));
+            result
+        }
+    })
+}
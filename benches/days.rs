@@ -0,0 +1,71 @@
+use aoc_macro::bench_days;
+use aoc25::{Day, DayImpl};
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+use std::fs;
+
+// Benchmarks every day that has either a cached real input (see the `auto` command)
+// or an example input. Use `cargo bench -- day05` to only benchmark a single day,
+// or `cargo bench -- day05/two` to only benchmark a single step.
+
+/// Loads the cached real input of a day, falling back to the example input.
+fn load_input(day: u8) -> Option<(String, &'static str)> {
+    if let Ok(input) = fs::read_to_string(format!("./.aoc25_cache/input{:02}.txt", day)) {
+        return Some((input, "cached input"));
+    }
+
+    let example = fs::read_to_string(format!(
+        "{}/src/days/test_inputs/test{:02}.txt",
+        env!("CARGO_MANIFEST_DIR"),
+        day
+    ))
+    .ok()?;
+
+    if example.trim().is_empty() {
+        // Days without an example haven't been implemented yet.
+        return None;
+    }
+
+    Some((example, "example input"))
+}
+
+fn bench_day<D, T>(day: u8, c: &mut Criterion)
+where
+    D: DayImpl<T>,
+    T: Clone,
+{
+    let Some((input, source)) = load_input(day) else {
+        return;
+    };
+    let input = input.trim_end();
+
+    eprintln!("Benchmarking day {} using the {}.", day, source);
+
+    let mut group = c.benchmark_group(format!("day{:02}", day));
+
+    group.bench_function("init", |b| b.iter(|| D::init(black_box(input))));
+
+    let (solution, data) = D::init(input);
+    group.bench_function("one", |b| {
+        b.iter_batched_ref(
+            || data.clone(),
+            |data| solution.solve(data, D::one),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("two", |b| {
+        b.iter_batched_ref(
+            || data.clone(),
+            |data| solution.solve(data, D::two),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn bench_all_days(c: &mut Criterion) {
+    bench_days!();
+}
+
+criterion_group!(benches, bench_all_days);
+criterion_main!(benches);
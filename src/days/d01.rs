@@ -1,4 +1,7 @@
-use super::{Answer, Day, DayImpl};
+use super::{
+    Answer, Day, DayImpl, InputProblem,
+    utils::{Rng, math},
+};
 
 const CURRENT_DAY: u8 = 1;
 
@@ -37,27 +40,13 @@ impl From<&str> for RotationSequence {
     }
 }
 
-/**
- * Wraps a number.
- *
- * number=245, min=0, max=99 => 45
- */
-fn wrap_number(number: i16, min: i16, max: i16) -> i16 {
-    let range = max - min + 1;
-    let mut result = (number - min) % range;
-    if result < 0 {
-        result += range;
-    }
-    result + min
-}
-
 impl RotationSequence {
     fn get_full_rotation_zero_count(&self) -> usize {
         let mut current: i16 = 50;
         let mut count = 0;
 
         for rotation in &self.0 {
-            current = wrap_number(current + rotation.as_signed_int(), 0, 99);
+            current = math::mod_euclid(current + rotation.as_signed_int(), 100);
 
             if current == 0 {
                 count += 1;
@@ -71,6 +60,28 @@ impl RotationSequence {
         let mut current: i16 = 50;
         let mut count = 0;
 
+        for rotation in &self.0 {
+            let amount = rotation.as_signed_int() as i32;
+            let position = current as i32;
+
+            // count the multiples of 100 passed or landed on while turning.
+            count += if amount >= 0 {
+                (position + amount) / 100
+            } else {
+                ((100 - position) % 100 - amount) / 100
+            } as usize;
+
+            current = math::mod_euclid(current + rotation.as_signed_int(), 100);
+        }
+
+        count
+    }
+
+    /// Reference implementation of [`Self::get_partial_rotation_zero_count`], turning the dial one step at a time.
+    fn get_partial_rotation_zero_count_stepping(&self) -> usize {
+        let mut current: i16 = 50;
+        let mut count = 0;
+
         for rotation in &self.0 {
             let mut current_rotation = *rotation;
 
@@ -88,7 +99,7 @@ impl RotationSequence {
                     }
                 }
 
-                current = wrap_number(current, 0, 99);
+                current = math::mod_euclid(current, 100);
 
                 if current == 0 {
                     count += 1;
@@ -115,10 +126,43 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
     }
 
     fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_full_rotation_zero_count() as u64)
+        Answer::from(data.get_full_rotation_zero_count())
     }
 
     fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_partial_rotation_zero_count() as u64)
+        Answer::from(data.get_partial_rotation_zero_count())
+    }
+
+    fn validate(input: &str) -> Option<Vec<InputProblem>> {
+        let mut problems = Vec::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let Some(amount) = line.strip_prefix(['L', 'R']) else {
+                problems.push(InputProblem::at(i, format!("`{}` doesn't start with L or R", line)));
+                continue;
+            };
+
+            if amount.parse::<i16>().map_or(true, |v| v < 0) {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("`{}` is not a number between 0 and {}", amount, i16::MAX),
+                ));
+            }
+        }
+
+        Some(problems)
+    }
+
+    /// Generates `size` rotations of up to 999 clicks.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let rotations: Vec<String> = (0..size)
+            .map(|_| format!("{}{}", rng.choose(&['L', 'R']), rng.range(1..=999)))
+            .collect();
+
+        Some(rotations.join("\n"))
+    }
+
+    fn reference_two(&self, data: &mut Data) -> Option<Answer> {
+        Some(Answer::from(data.get_partial_rotation_zero_count_stepping()))
     }
 }
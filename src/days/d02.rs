@@ -1,19 +1,11 @@
-use super::{Answer, Day, DayImpl};
+use std::collections::BTreeSet;
 
-const CURRENT_DAY: u8 = 2;
-
-/// Turns a number into a Vector of individual digits.
-fn to_digits(mut number: u64) -> Vec<u8> {
-    let mut digits = Vec::with_capacity(16);
-
-    while number > 0 {
-        digits.push((number % 10) as u8);
-        number /= 10;
-    }
+use super::{
+    Answer, Day, DayImpl, InputProblem,
+    utils::{Interval, Rng, math, parse},
+};
 
-    digits.reverse();
-    digits
-}
+const CURRENT_DAY: u8 = 2;
 
 /// Checks if a slice is made up of a repetition of a specified length and returns that repetition pattern.
 fn detect_repetition_of_length<T: PartialEq>(slice: &[T], sequence_length: usize) -> Option<&[T]> {
@@ -21,7 +13,7 @@ fn detect_repetition_of_length<T: PartialEq>(slice: &[T], sequence_length: usize
 
     // if the length of the slice is not a multiple of the sequence length,
     // it cannot be made up of repetitions of this sequence.
-    if sequence_length == 0 || slice_length % sequence_length != 0 {
+    if sequence_length == 0 || !slice_length.is_multiple_of(sequence_length) {
         return None;
     }
 
@@ -38,10 +30,10 @@ fn detect_repetition_of_length<T: PartialEq>(slice: &[T], sequence_length: usize
     if matches { Some(sequence) } else { None }
 }
 
-/// Detects all repetitions in a slice making up the complete slice.
+/// Detects all repetitions in a slice making up the complete slice, together with their lengths.
 ///
 /// Example:
-/// `[1,1,1,1] => [([1], 4), ([1, 1], 2)]`
+/// `[1,1,1,1] => [([1], 1), ([1, 1], 2)]`
 fn detect_repetition<T: PartialEq>(slice: &[T]) -> Vec<(&[T], usize)> {
     let slice_length = slice.len();
 
@@ -54,25 +46,41 @@ fn detect_repetition<T: PartialEq>(slice: &[T]) -> Vec<(&[T], usize)> {
 }
 
 #[derive(Debug, Clone)]
-pub struct ProductIdRange {
-    start: u64,
-    end: u64,
-}
+pub struct ProductIdRange(Interval<u64>);
 
-impl From<&str> for ProductIdRange {
-    fn from(value: &str) -> Self {
-        let (start, end) = value.split_once('-').expect("unexpected input");
+impl ProductIdRange {
+    fn iter(&self) -> impl Iterator<Item = u64> {
+        self.0.start..=self.0.end
+    }
+
+    /// Generates all IDs within the range which are made up of a sequence of digits repeated
+    /// a number of times accepted by `accept_repetitions`.
+    ///
+    /// Instead of checking every ID, this generates the repeated IDs for every sequence length directly:
+    /// A sequence of `k` digits repeated `r` times is the sequence multiplied by `1 + 10^k + ... + 10^(k * (r - 1))`.
+    fn repeated_ids(&self, accept_repetitions: impl Fn(u32) -> bool) -> BTreeSet<u64> {
+        let mut ids = BTreeSet::new();
+
+        for length in math::digit_count(self.0.start)..=math::digit_count(self.0.end) {
+            for sequence_length in 1..=(length / 2) {
+                let repetitions = length / sequence_length;
+                if !length.is_multiple_of(sequence_length) || !accept_repetitions(repetitions) {
+                    continue;
+                }
+
+                let multiplier: u64 = (0..repetitions)
+                    .map(|i| 10_u64.pow(sequence_length * i))
+                    .sum();
+                let lowest = 10_u64
+                    .pow(sequence_length - 1)
+                    .max(self.0.start.div_ceil(multiplier));
+                let highest = (10_u64.pow(sequence_length) - 1).min(self.0.end / multiplier);
 
-        Self {
-            start: start.parse().expect("Unexpected input"),
-            end: end.parse().expect("Unexpected input"),
+                ids.extend((lowest..=highest).map(|sequence| sequence * multiplier));
+            }
         }
-    }
-}
 
-impl ProductIdRange {
-    fn iter(&self) -> impl Iterator<Item = u64> {
-        self.start..=self.end
+        ids
     }
 }
 
@@ -81,19 +89,45 @@ pub struct ProductIdRangeList(Vec<ProductIdRange>);
 
 impl From<&str> for ProductIdRangeList {
     fn from(value: &str) -> Self {
-        Self(value.split(',').map(|v| v.into()).collect())
+        Self(
+            parse::parse_list::<(u64, u64)>(value, ",", r"(\d+)-(\d+)")
+                .expect("Unexpected input")
+                .into_iter()
+                .map(|(start, end)| {
+                    ProductIdRange(Interval::new(start, end).expect("Unexpected input"))
+                })
+                .collect(),
+        )
     }
 }
 
 impl ProductIdRangeList {
     fn sum_twice_invalid_ids(&self) -> u64 {
+        self.0
+            .iter()
+            .map(|range| range.repeated_ids(|repetitions| repetitions == 2).iter().sum::<u64>())
+            .sum()
+    }
+
+    fn sum_any_invalid_ids(&self) -> u64 {
+        self.0
+            .iter()
+            .map(|range| range.repeated_ids(|repetitions| repetitions >= 2).iter().sum::<u64>())
+            .sum()
+    }
+
+    /// Reference implementation of [`Self::sum_twice_invalid_ids`], checking every ID.
+    fn sum_twice_invalid_ids_by_iteration(&self) -> u64 {
+        let mut digits = Vec::with_capacity(20);
+
         self.0
             .iter()
             .flat_map(|range| range.iter())
             .filter(|id| {
-                let digits = to_digits(*id);
+                digits.clear();
+                digits.extend(math::digits(*id));
 
-                if digits.len() % 2 != 0 {
+                if !digits.len().is_multiple_of(2) {
                     return false;
                 }
 
@@ -103,12 +137,16 @@ impl ProductIdRangeList {
             .sum()
     }
 
-    fn sum_any_invalid_ids(&self) -> u64 {
+    /// Reference implementation of [`Self::sum_any_invalid_ids`], checking every ID.
+    fn sum_any_invalid_ids_by_iteration(&self) -> u64 {
+        let mut digits = Vec::with_capacity(20);
+
         self.0
             .iter()
             .flat_map(|range| range.iter())
             .filter(|id| {
-                let digits = to_digits(*id);
+                digits.clear();
+                digits.extend(math::digits(*id));
 
                 !detect_repetition(&digits).is_empty()
             })
@@ -138,4 +176,58 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
     fn two(&self, data: &mut Data) -> Answer {
         Answer::Number(data.sum_any_invalid_ids())
     }
+
+    fn validate(input: &str) -> Option<Vec<InputProblem>> {
+        let mut problems = Vec::new();
+
+        if input.lines().count() > 1 {
+            problems.push(InputProblem::general(
+                "expected all ranges on a single line",
+            ));
+        }
+
+        for (i, line) in input.lines().enumerate() {
+            for range in line.split(',') {
+                let Some((start, end)) = range.split_once('-') else {
+                    problems.push(InputProblem::at(i, format!("`{}` is not a range", range)));
+                    continue;
+                };
+
+                match (start.parse::<u64>(), end.parse::<u64>()) {
+                    (Ok(start), Ok(end)) if start > end => problems.push(InputProblem::at(
+                        i,
+                        format!("the range `{}` ends before it starts", range),
+                    )),
+                    (Ok(_), Ok(_)) => {}
+                    _ => problems.push(InputProblem::at(
+                        i,
+                        format!("the bounds of `{}` are not numbers", range),
+                    )),
+                }
+            }
+        }
+
+        Some(problems)
+    }
+
+    /// Generates `size` ranges of IDs with up to 10 digits, each spanning up to 100000 IDs.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let ranges: Vec<String> = (0..size)
+            .map(|_| {
+                let digits = rng.range(1..=10) as u32;
+                let start = rng.range(10_u64.pow(digits - 1)..=10_u64.pow(digits) - 1);
+                format!("{}-{}", start, start + rng.range(0..=100000))
+            })
+            .collect();
+
+        Some(ranges.join(","))
+    }
+
+    fn reference_one(&self, data: &mut Data) -> Option<Answer> {
+        Some(Answer::Number(data.sum_twice_invalid_ids_by_iteration()))
+    }
+
+    fn reference_two(&self, data: &mut Data) -> Option<Answer> {
+        Some(Answer::Number(data.sum_any_invalid_ids_by_iteration()))
+    }
 }
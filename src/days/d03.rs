@@ -1,4 +1,4 @@
-use super::{Answer, Day, DayImpl};
+use super::{Answer, Day, DayImpl, InputProblem, utils::Rng};
 
 const CURRENT_DAY: u8 = 3;
 
@@ -20,6 +20,8 @@ impl From<&str> for BatteryBank {
     }
 }
 
+// Not memoized: the first digit with enough batteries after it always leads to a result, so the
+// recursion barely revisits any subproblems, and caching them only costs time.
 fn get_highest_possible_number(sequence: &[u8], num_digits: u32) -> Option<u64> {
     for digit in (0..=9).rev() {
         if let Some((position, digit)) = sequence.iter().enumerate().find(|v| *v.1 == digit) {
@@ -87,4 +89,37 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
     fn two(&self, data: &mut Data) -> Answer {
         Answer::Number(data.get_maximum_joltage(12))
     }
+
+    fn validate(input: &str) -> Option<Vec<InputProblem>> {
+        let mut problems = Vec::new();
+
+        for (i, line) in input.lines().enumerate() {
+            if let Some(c) = line.chars().find(|c| !matches!(c, '1'..='9')) {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("unexpected character `{}`, expected digits from 1 to 9", c),
+                ));
+            } else if line.len() < 12 {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("the bank has only {} batteries, expected at least 12", line.len()),
+                ));
+            }
+        }
+
+        Some(problems)
+    }
+
+    /// Generates `size` banks of 100 batteries each.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let banks: Vec<String> = (0..size)
+            .map(|_| {
+                (0..100)
+                    .map(|_| char::from_digit(rng.range(1..=9) as u32, 10).unwrap())
+                    .collect()
+            })
+            .collect();
+
+        Some(banks.join("\n"))
+    }
 }
@@ -1,85 +1,109 @@
-use std::collections::HashMap;
+use colored::Color;
 
-use super::{Answer, Day, DayImpl};
+use super::{
+    Answer, Day, DayImpl, InputProblem,
+    utils::{
+        BitGrid, Map, Rng,
+        automaton::{Automaton, Neighbourhood},
+    },
+};
+use crate::visualize::{Frame, Style};
 
 const CURRENT_DAY: u8 = 4;
 
 #[derive(Debug, Clone)]
 pub struct PaperDepartmentMap {
-    paper_rolls: HashMap<(isize, isize), bool>,
-    width: usize,
-    height: usize,
+    /// Whether there is a paper roll on each tile.
+    tiles: Map<bool>,
+    /// The same tiles packed into words, used by the optimized implementation.
+    paper_rolls: BitGrid,
 }
 
 impl From<&str> for PaperDepartmentMap {
     fn from(value: &str) -> Self {
-        let (mut width, mut height) = (0, 0);
-        let mut paper_rolls = HashMap::new();
+        let tiles = Map::from_str_with(value, |char| char == '@');
+        let paper_rolls = BitGrid::from_map(&tiles, |&paper_roll| paper_roll);
 
-        value.lines().enumerate().for_each(|(y, line)| {
-            if y + 1 > height {
-                height = y + 1
-            }
-
-            line.chars().enumerate().for_each(|(x, char)| {
-                if x + 1 > width {
-                    width = x + 1
-                }
-
-                if char == '@' {
-                    paper_rolls.insert((x as isize, y as isize), true);
-                }
-            })
-        });
-
-        Self {
-            paper_rolls,
-            width,
-            height,
-        }
+        Self { tiles, paper_rolls }
     }
 }
 
 impl PaperDepartmentMap {
-    fn get_neighbours(&self, subject_position: &(isize, isize)) -> HashMap<(isize, isize), bool> {
-        let mut neighbours = HashMap::with_capacity(8);
+    fn count_accessible_paper_rolls(&self) -> usize {
+        self.paper_rolls
+            .count_where(|rolls, neighbours| rolls & neighbours.less_than(4))
+    }
 
-        for x in -1..=1 {
-            for y in -1..=1 {
-                if x == 0 && y == 0 {
-                    continue;
-                }
+    /// Removes accessible paper rolls in waves, until none are accessible.
+    fn try_remove_all(&mut self) -> usize {
+        let mut total_removed = 0;
 
-                let position = (subject_position.0 + x, subject_position.1 + y);
+        loop {
+            let removed = self
+                .paper_rolls
+                .update(|rolls, neighbours| rolls & !neighbours.less_than(4));
 
-                if let Some(paper_roll) = self.paper_rolls.get(&position) {
-                    neighbours.insert(position, *paper_roll);
-                }
+            if removed == 0 {
+                return total_removed;
             }
+
+            total_removed += removed;
         }
+    }
 
-        neighbours
+    fn to_tiled(&self) -> TiledPaperDepartmentMap {
+        TiledPaperDepartmentMap {
+            tiles: self.tiles.clone(),
+        }
     }
+}
+
+type RemovalRule = fn(&bool, &[bool]) -> bool;
+
+/// The paper rolls stored tile by tile, used by the visualization and, expressed as a cellular automaton,
+/// as reference implementation.
+#[derive(Debug, Clone)]
+struct TiledPaperDepartmentMap {
+    tiles: Map<bool>,
+}
 
-    fn get_all_positions(&self) -> Vec<(isize, isize)> {
-        (0..self.width as isize)
-            .flat_map(|x| (0..self.height as isize).map(move |y| (x, y)))
+impl TiledPaperDepartmentMap {
+    fn get_accessible_paper_rolls(&self) -> Vec<(usize, usize)> {
+        self.tiles
+            .find_all(|&paper_roll| paper_roll)
+            .filter(|&(x, y)| {
+                self.tiles
+                    .neighbours8(x, y)
+                    .filter(|(_, paper_roll)| **paper_roll)
+                    .count()
+                    < 4
+            })
             .collect()
     }
 
-    fn get_accessible_paper_rolls(&self) -> Vec<(isize, isize)> {
-        self.get_all_positions()
-            .into_iter()
-            .filter(|position| self.paper_rolls.contains_key(position))
-            .filter(|position| self.get_neighbours(position).len() < 4)
-            .collect()
+    /// An automaton removing every paper roll with less than 4 neighbouring paper rolls in each step.
+    fn removal_automaton(&self) -> Automaton<Map<bool>, RemovalRule> {
+        Automaton::new(
+            self.tiles.clone(),
+            Neighbourhood::Moore,
+            |&paper_roll, neighbours| {
+                paper_roll && neighbours.iter().filter(|&&neighbour| neighbour).count() >= 4
+            },
+        )
     }
 
     fn count_accessible_paper_rolls(&self) -> usize {
-        self.get_accessible_paper_rolls().len()
+        self.removal_automaton().step()
     }
 
-    fn try_remove_all(&mut self) -> usize {
+    fn try_remove_all(&self) -> usize {
+        self.removal_automaton().run_worklist(None).total_changes()
+    }
+
+    /// Removes accessible paper rolls in waves, until none are accessible.
+    ///
+    /// `on_wave` is called with the map and the positions about to be removed before each wave.
+    fn try_remove_all_with(&mut self, mut on_wave: impl FnMut(&Self, &[(usize, usize)])) -> usize {
         let mut total_removed = 0;
 
         loop {
@@ -90,13 +114,29 @@ impl PaperDepartmentMap {
                 return total_removed;
             }
 
-            to_remove.iter().for_each(|position| {
-                self.paper_rolls.remove(position);
+            on_wave(self, &to_remove);
+
+            to_remove.iter().for_each(|&(x, y)| {
+                self.tiles.set(x, y, false);
             });
 
             total_removed += to_remove.len();
         }
     }
+
+    fn to_frame(&self, highlighted: &[(usize, usize)]) -> Frame {
+        let (width, height) = self.tiles.dimensions();
+        let mut frame = Frame::new(width, height);
+
+        for (x, y) in self.tiles.find_all(|&paper_roll| paper_roll) {
+            frame.set(x, y, '@', Style::fg(Color::White));
+        }
+        for &(x, y) in highlighted {
+            frame.set(x, y, '@', Style::fg(Color::Red).bold());
+        }
+
+        frame
+    }
 }
 
 type Data = PaperDepartmentMap;
@@ -114,10 +154,74 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
     }
 
     fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.count_accessible_paper_rolls() as u64)
+        Answer::from(data.count_accessible_paper_rolls())
     }
 
     fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.try_remove_all() as u64)
+        Answer::from(data.try_remove_all())
+    }
+
+    fn validate(input: &str) -> Option<Vec<InputProblem>> {
+        let mut problems = Vec::new();
+        let width = input.lines().next().map(|v| v.len()).unwrap_or(0);
+
+        for (i, line) in input.lines().enumerate() {
+            if let Some(c) = line.chars().find(|c| !matches!(c, '@' | '.')) {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("unexpected character `{}`, expected `@` or `.`", c),
+                ));
+            }
+            if line.len() != width {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("the line is {} long, but the first is {}", line.len(), width),
+                ));
+            }
+        }
+
+        Some(problems)
+    }
+
+    fn reference_one(&self, data: &mut Data) -> Option<Answer> {
+        Some(Answer::from(data.to_tiled().count_accessible_paper_rolls()))
+    }
+
+    fn reference_two(&self, data: &mut Data) -> Option<Answer> {
+        Some(Answer::from(data.to_tiled().try_remove_all()))
+    }
+
+    /// Generates a `size` by `size` map, with paper rolls on about 60% of the positions.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let lines: Vec<String> = (0..size)
+            .map(|_| {
+                (0..size)
+                    .map(|_| if rng.chance(0.6) { '@' } else { '.' })
+                    .collect()
+            })
+            .collect();
+
+        Some(lines.join("\n"))
+    }
+
+    /// Shows every wave of removed paper rolls, until none are accessible anymore.
+    fn visualize(&self, data: &mut Data, emit: &mut dyn FnMut(Frame)) -> bool {
+        let mut tiled = data.to_tiled();
+        let mut wave = 0;
+        let total_removed = tiled.try_remove_all_with(|map, to_remove| {
+            wave += 1;
+            emit(map.to_frame(to_remove).with_caption(format!(
+                "Wave {}: removing {} accessible paper rolls",
+                wave,
+                to_remove.len()
+            )));
+        });
+
+        emit(tiled.to_frame(&[]).with_caption(format!(
+            "Done: removed {} paper rolls in {} waves",
+            total_removed, wave
+        )));
+
+        true
     }
 }
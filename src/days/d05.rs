@@ -1,48 +1,46 @@
 use crate::dprintln;
 
-use super::{Answer, Day, DayImpl};
+use super::{
+    Answer, Day, DayImpl, InputProblem,
+    utils::{IntervalSet, Rng, parse},
+};
 
 const CURRENT_DAY: u8 = 5;
 
 #[derive(Debug, Clone)]
 pub struct Inventory {
-    fresh_ranges: Vec<(usize, usize)>,
+    fresh_ranges: IntervalSet<usize>,
     available_ingredients: Vec<usize>,
 }
 
 impl From<&str> for Inventory {
     fn from(value: &str) -> Self {
-        let (fresh_ranges_str, available_ingredients_str) = value.split_once("\n\n").unwrap();
+        let sections = parse::sections(value);
+        let (fresh_ranges_section, available_ingredients_section) = (sections[0], sections[1]);
+
+        let fresh_ranges: IntervalSet<usize> = fresh_ranges_section
+            .parse_lines::<(usize, usize)>(r"(\d+)-(\d+)")
+            .expect("Unexpected input")
+            .into_iter()
+            .map(|(start, end)| start..=end)
+            .collect();
+        dprintln!("FRESH RANGES: {:?}", fresh_ranges);
 
         Self {
-            fresh_ranges: fresh_ranges_str
-                .lines()
-                .map(|line| {
-                    let (start, end) = line.split_once('-').unwrap();
-                    (start.parse().unwrap(), end.parse().unwrap())
-                })
-                .collect(),
-            available_ingredients: available_ingredients_str
-                .lines()
-                .map(|v| v.parse().unwrap())
+            fresh_ranges,
+            available_ingredients: available_ingredients_section
+                .parse_lines::<(usize,)>(r"(\d+)")
+                .expect("Unexpected input")
+                .into_iter()
+                .map(|(v,)| v)
                 .collect(),
         }
     }
 }
 
 impl Inventory {
-    fn is_in_range(value: usize, range: &(usize, usize)) -> bool {
-        range.0 <= value && value <= range.1
-    }
-
     fn is_fresh(&self, ingredient_id: usize) -> bool {
-        for range in &self.fresh_ranges {
-            if Self::is_in_range(ingredient_id, range) {
-                return true;
-            }
-        }
-
-        false
+        self.fresh_ranges.contains(ingredient_id)
     }
 
     fn get_available_fresh_ingredients(&self) -> Vec<usize> {
@@ -53,37 +51,8 @@ impl Inventory {
             .collect()
     }
 
-    fn get_fresh_ingredient_count(&self) -> usize {
-        let mut ranges = self.fresh_ranges.clone();
-
-        ranges.sort_by_key(|k| k.0);
-
-        let mut merged_ranges: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
-        merged_ranges.push(ranges[0]);
-
-        for (start, end) in ranges.into_iter().skip(1) {
-            dprintln!("RANGE: {} - {}", start, end);
-            if let Some(last_range) = merged_ranges.last_mut() {
-                if start <= last_range.1 + 1 {
-                    dprintln!(
-                        "  Starts within last range (which ends at {})",
-                        last_range.1
-                    );
-                    dprintln!("    Extending last range to {}", end.max(last_range.1));
-                    last_range.1 = end.max(last_range.1);
-                } else {
-                    dprintln!("  No overlap. adding.");
-                    merged_ranges.push((start, end));
-                }
-            }
-        }
-
-        dprintln!("=> {:?}", merged_ranges);
-
-        merged_ranges
-            .iter()
-            .map(|(start, end)| end - start + 1)
-            .sum()
+    fn get_fresh_ingredient_count(&self) -> u128 {
+        self.fresh_ranges.len()
     }
 }
 
@@ -102,10 +71,78 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
     }
 
     fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_available_fresh_ingredients().len() as u64)
+        Answer::from(data.get_available_fresh_ingredients().len())
     }
 
     fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_fresh_ingredient_count() as u64)
+        Answer::from(data.get_fresh_ingredient_count())
+    }
+
+    fn validate(input: &str) -> Option<Vec<InputProblem>> {
+        let mut problems = Vec::new();
+        let lines: Vec<&str> = input.lines().collect();
+
+        let Some(separator) = lines.iter().position(|v| v.is_empty()) else {
+            problems.push(InputProblem::general(
+                "no blank line separating the fresh ranges from the available ingredients",
+            ));
+            return Some(problems);
+        };
+
+        for (i, line) in lines.iter().enumerate().take(separator) {
+            let range = line
+                .split_once('-')
+                .and_then(|(start, end)| Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?)));
+
+            match range {
+                Some((start, end)) if start > end => problems.push(InputProblem::at(
+                    i,
+                    format!("the range `{}` ends before it starts", line),
+                )),
+                Some(_) => {}
+                None => problems.push(InputProblem::at(i, format!("`{}` is not a range", line))),
+            }
+        }
+
+        for (i, line) in lines.iter().enumerate().skip(separator + 1) {
+            if line.is_empty() {
+                problems.push(InputProblem::at(i, "unexpected blank line"));
+            } else if line.parse::<usize>().is_err() {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("`{}` is not an ingredient ID", line),
+                ));
+            }
+        }
+
+        Some(problems)
+    }
+
+    /// Generates `size` fresh ingredient ID ranges, and `size` available ingredient IDs.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        const MAX_ID: u64 = 100_000_000_000_000;
+
+        let ranges: Vec<(u64, u64)> = (0..size)
+            .map(|_| {
+                let start = rng.range(1..=MAX_ID);
+                (start, start + rng.range(0..=MAX_ID / 1000))
+            })
+            .collect();
+        // about half of the ingredients are picked from within a range, so some of them are fresh.
+        let ingredients: Vec<String> = (0..size)
+            .map(|_| match rng.chance(0.5) {
+                true => {
+                    let (start, end) = *rng.choose(&ranges);
+                    rng.range(start..=end).to_string()
+                }
+                false => rng.range(1..=MAX_ID).to_string(),
+            })
+            .collect();
+
+        let ranges: Vec<String> = ranges
+            .iter()
+            .map(|(start, end)| format!("{}-{}", start, end))
+            .collect();
+        Some(ranges.join("\n") + "\n\n" + &ingredients.join("\n"))
     }
 }
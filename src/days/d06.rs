@@ -1,8 +1,9 @@
-use std::collections::HashMap;
-
 use crate::dprintln;
 
-use super::{Answer, Day, DayImpl};
+use super::{
+    Answer, Day, DayImpl, InputProblem,
+    utils::{Map, MapView, Rng},
+};
 
 const CURRENT_DAY: u8 = 6;
 
@@ -21,11 +22,11 @@ impl Operation {
     }
 }
 
-impl From<&str> for Operation {
-    fn from(value: &str) -> Self {
+impl From<&char> for Operation {
+    fn from(value: &char) -> Self {
         match value {
-            "+" => Self::Addition,
-            "*" => Self::Multiply,
+            '+' => Self::Addition,
+            '*' => Self::Multiply,
             _ => panic!("Invalid Operation"),
         }
     }
@@ -38,43 +39,39 @@ struct HomeworkCalculation {
 }
 
 impl HomeworkCalculation {
-    pub fn normal_from_column(value: &[String]) -> Self {
-        dprintln!("normal_from_column: {:?}", value);
+    /// Reads the numbers of a problem line by line.
+    pub fn normal_from_view(numbers: MapView<char>, operation: Operation) -> Self {
+        dprintln!("normal_from_view: {}", numbers.to_map());
         Self {
-            numbers: value[..value.len() - 1]
-                .iter()
-                .map(|v| v.trim().parse().unwrap())
+            numbers: numbers
+                .rows()
+                .map(|row| {
+                    let line: String = row.row(0).collect();
+                    line.trim().parse().unwrap()
+                })
                 .collect(),
-            operation: Operation::from(value.last().unwrap().as_str().trim()),
+            operation,
         }
     }
 
-    pub fn cephalopod_from_column(value: &[String]) -> Self {
-        dprintln!("cephalopod_from_column: {:?}", value);
-        let mut numbers: HashMap<usize, usize> =
-            HashMap::with_capacity(value.first().unwrap().len());
-
-        value.iter().for_each(|line| {
-            line.chars().enumerate().for_each(|(char_num, char)| {
-                if !char.is_ascii_digit() {
-                    return;
-                }
-                let mut num = *numbers.get(&char_num).unwrap_or(&0);
-
-                dprintln!("num {:?} char_num {:?} char {:?}", num, char_num, char);
-
-                num = num * 10 + char.to_digit(10).unwrap() as usize;
-
-                numbers.insert(char_num, num);
+    /// Reads the numbers of a problem column by column, with the most significant digit at the top.
+    pub fn cephalopod_from_view(numbers: MapView<char>, operation: Operation) -> Self {
+        dprintln!("cephalopod_from_view: {}", numbers.to_map());
+        let numbers: Vec<usize> = numbers
+            .columns()
+            .filter_map(|column| {
+                column
+                    .column(0)
+                    .filter_map(|c| c.to_digit(10))
+                    .fold(None, |num, digit| {
+                        Some(num.unwrap_or(0) * 10 + digit as usize)
+                    })
             })
-        });
+            .collect();
 
-        dprintln!("cephalopod_from_column result: {:?}\n\n", numbers);
+        dprintln!("cephalopod_from_view result: {:?}\n\n", numbers);
 
-        Self {
-            numbers: numbers.into_values().collect(),
-            operation: Operation::from(value.last().unwrap().as_str().trim()),
-        }
+        Self { numbers, operation }
     }
 
     pub fn get_result(&self) -> usize {
@@ -84,59 +81,54 @@ impl HomeworkCalculation {
 
 #[derive(Debug, Clone)]
 pub struct HomeworkSheet {
-    columns: Vec<Vec<String>>,
+    sheet: Map<char>,
+    column_starts: Vec<usize>,
 }
 
 impl From<&str> for HomeworkSheet {
     fn from(value: &str) -> Self {
-        let last_line = value.lines().last().unwrap();
-        let column_starts: Vec<usize> = last_line
-            .match_indices(|c: char| !c.is_whitespace())
+        let sheet = Map::from_str_padded(value, ' ', |c| c);
+        let (_, height) = sheet.dimensions();
+        let column_starts: Vec<usize> = sheet
+            .row(height - 1)
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace())
             .map(|(i, _)| i)
             .collect();
 
         dprintln!("Column starts: {:?}", column_starts);
 
-        let mut split_lines: Vec<Vec<String>> = Vec::with_capacity(value.lines().count());
-        for line in value.lines() {
-            let mut split_line = Vec::with_capacity(column_starts.len());
-
-            dprintln!("LINE {:?}", line);
-
-            for i in 0..column_starts.len() {
-                let start = column_starts[i];
-                let end = column_starts.get(i + 1).copied().unwrap_or(line.len());
-
-                split_line.push(line[start..end.min(line.len())].to_owned());
-            }
-
-            split_lines.push(split_line)
-        }
-
-        dprintln!("{:?}", split_lines);
-
-        let num_cols = split_lines.first().unwrap().len();
-
         Self {
-            columns: (0..num_cols)
-                .map(|i| split_lines.iter().map(|line| line[i].to_owned()).collect())
-                .collect(),
+            sheet,
+            column_starts,
         }
     }
 }
 
 impl HomeworkSheet {
+    /// Splits the sheet into the numbers and the operation of every problem.
+    fn get_problems(&self) -> impl Iterator<Item = (MapView<'_, char>, Operation)> {
+        let (width, height) = self.sheet.dimensions();
+
+        self.column_starts.iter().enumerate().map(move |(i, &start)| {
+            let end = self.column_starts.get(i + 1).copied().unwrap_or(width);
+            let operation = Operation::from(self.sheet.get(start, height - 1).unwrap());
+
+            (self.sheet.view(start, 0, end - start, height - 1), operation)
+        })
+    }
+
     fn get_normal_homework_calculations(&self) -> Vec<HomeworkCalculation> {
-        self.columns
-            .iter()
-            .map(|v| HomeworkCalculation::normal_from_column(v))
+        self.get_problems()
+            .map(|(numbers, operation)| HomeworkCalculation::normal_from_view(numbers, operation))
             .collect()
     }
 
     fn get_cephalopod_homework_calculations(&self) -> Vec<HomeworkCalculation> {
-        self.columns
-            .iter()
-            .map(|v| HomeworkCalculation::cephalopod_from_column(v))
+        self.get_problems()
+            .map(|(numbers, operation)| {
+                HomeworkCalculation::cephalopod_from_view(numbers, operation)
+            })
             .collect()
     }
 
@@ -170,10 +162,108 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
     }
 
     fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_normal_results().into_iter().sum::<usize>() as u64)
+        Answer::from(data.get_normal_results().into_iter().sum::<usize>())
     }
 
     fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.get_cephalopod_results().into_iter().sum::<usize>() as u64)
+        Answer::from(data.get_cephalopod_results().into_iter().sum::<usize>())
+    }
+
+    fn validate(input: &str) -> Option<Vec<InputProblem>> {
+        let mut problems = Vec::new();
+        let lines: Vec<&str> = input.lines().collect();
+
+        let Some((operator_index, operators)) = lines.iter().enumerate().next_back() else {
+            return Some(problems);
+        };
+
+        if operators.chars().any(|c| c.is_ascii_digit()) {
+            problems.push(InputProblem::general(
+                "the last line contains numbers, the line of operators is missing",
+            ));
+            return Some(problems);
+        }
+        if let Some(c) = operators.chars().find(|c| !matches!(c, '+' | '*' | ' ')) {
+            problems.push(InputProblem::at(
+                operator_index,
+                format!("unexpected character `{}`, expected `+`, `*` or spaces", c),
+            ));
+        }
+        if operators.starts_with(' ') {
+            problems.push(InputProblem::at(
+                operator_index,
+                "the first operator is not at the start of the line",
+            ));
+        }
+
+        let column_starts: Vec<usize> = operators
+            .match_indices(|c: char| !c.is_whitespace())
+            .map(|(i, _)| i)
+            .collect();
+
+        for (i, line) in lines.iter().enumerate().take(operator_index) {
+            if let Some(c) = line.chars().find(|c| !c.is_ascii_digit() && *c != ' ') {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("unexpected character `{}`, expected digits or spaces", c),
+                ));
+                continue;
+            }
+
+            for (column, &start) in column_starts.iter().enumerate() {
+                let end = column_starts.get(column + 1).copied().unwrap_or(line.len());
+                let number = line.get(start..end.min(line.len())).unwrap_or("").trim();
+
+                if number.is_empty() {
+                    problems.push(InputProblem::at(
+                        i,
+                        format!("no number in the problem starting at column {}", start + 1),
+                    ));
+                } else if number.contains(' ') {
+                    problems.push(InputProblem::at(
+                        i,
+                        format!(
+                            "multiple numbers in the problem starting at column {}",
+                            start + 1
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Some(problems)
+    }
+
+    /// Generates a worksheet of `size` problems, each with four numbers of up to four digits.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut lines = vec![String::new(); 5];
+
+        for problem in 0..size {
+            let numbers: Vec<String> = (0..4)
+                .map(|_| {
+                    let digits = rng.range(1..=4) as u32;
+                    rng.range(1..=10_u64.pow(digits) - 1).to_string()
+                })
+                .collect();
+            let width = numbers.iter().map(|v| v.len()).max().unwrap();
+            let right_aligned = rng.chance(0.5);
+
+            for (line, number) in lines.iter_mut().zip(&numbers) {
+                if problem != 0 {
+                    line.push(' ');
+                }
+                match right_aligned {
+                    true => line.push_str(&format!("{:>width$}", number)),
+                    false => line.push_str(&format!("{:<width$}", number)),
+                }
+            }
+
+            if problem != 0 {
+                lines[4].push(' ');
+            }
+            lines[4].push_str(&format!("{:<width$}", rng.choose(&['+', '*'])));
+        }
+
+        Some(lines.join("\n"))
     }
 }
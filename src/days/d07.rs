@@ -1,10 +1,23 @@
-use super::{Answer, Day, DayImpl};
+use colored::Color;
+
+use super::{
+    Answer, Day, DayImpl, InputProblem,
+    utils::{Map, Rng},
+};
+use crate::visualize::{Frame, Style};
 
 const CURRENT_DAY: u8 = 7;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ManifoldTile {
+    Empty,
+    Start,
+    Splitter,
+}
+
 #[derive(Debug, Clone)]
 pub struct TachyonManifold {
-    splitters: Vec<Vec<bool>>,
+    tiles: Map<ManifoldTile>,
     start_position: (usize, usize),
     depth: usize,
     width: usize,
@@ -12,29 +25,16 @@ pub struct TachyonManifold {
 
 impl From<&str> for TachyonManifold {
     fn from(value: &str) -> Self {
-        let mut start_pos = None;
-        let mut splitter_positions = Vec::new();
-        let mut width = 0;
-        let mut depth = 0;
-
-        value.lines().enumerate().for_each(|(y, line)| {
-            width = width.max(line.len());
-            depth = depth.max(y + 1);
-            line.chars().enumerate().for_each(|(x, char)| match char {
-                'S' => start_pos = Some((x, y)),
-                '^' => splitter_positions.push((x, y)),
-                _ => {}
-            })
+        let tiles = Map::from_str_with(value, |char| match char {
+            'S' => ManifoldTile::Start,
+            '^' => ManifoldTile::Splitter,
+            _ => ManifoldTile::Empty,
         });
-
-        let mut splitters = vec![vec![false; width]; depth];
-        for (x, y) in splitter_positions {
-            splitters[y][x] = true;
-        }
+        let (width, depth) = tiles.dimensions();
 
         Self {
-            splitters,
-            start_position: start_pos.unwrap(),
+            start_position: tiles.find(|&v| v == ManifoldTile::Start).unwrap(),
+            tiles,
             depth,
             width,
         }
@@ -42,6 +42,10 @@ impl From<&str> for TachyonManifold {
 }
 
 impl TachyonManifold {
+    fn is_splitter(&self, x: usize, y: usize) -> bool {
+        self.tiles.get(x, y) == Some(&ManifoldTile::Splitter)
+    }
+
     fn perform_splits(&self, rays: Vec<bool>, layer: usize) -> (usize, Vec<bool>) {
         let mut splits = 0;
         let mut new_rays = vec![false; self.width];
@@ -51,7 +55,7 @@ impl TachyonManifold {
                 continue;
             }
 
-            if self.splitters[layer][x] {
+            if self.is_splitter(x, layer) {
                 splits += 1;
 
                 new_rays[x - 1] = true;
@@ -79,14 +83,14 @@ impl TachyonManifold {
         splits
     }
 
-    fn perform_splits_overlapping(&self, rays: Vec<usize>, layer: usize) -> Vec<usize> {
+    fn perform_splits_overlapping(&self, rays: Vec<u128>, layer: usize) -> Vec<u128> {
         let mut new_rays = vec![0; self.width];
         for (x, &count) in rays.iter().enumerate() {
             if count == 0 {
                 continue;
             }
 
-            if self.splitters[layer][x] {
+            if self.is_splitter(x, layer) {
                 new_rays[x - 1] += count;
                 new_rays[x + 1] += count;
             } else {
@@ -96,7 +100,8 @@ impl TachyonManifold {
         new_rays
     }
 
-    fn count_paths(&self) -> usize {
+    /// Counts the paths a single ray can take. This grows exponentially with the depth of the manifold.
+    fn count_paths(&self) -> u128 {
         let mut rays = vec![0; self.width];
         rays[self.start_position.0] = 1;
 
@@ -104,7 +109,36 @@ impl TachyonManifold {
             rays = self.perform_splits_overlapping(rays, layer);
         }
 
-        rays.iter().sum::<usize>()
+        rays.iter().sum::<u128>()
+    }
+}
+
+impl TachyonManifold {
+    /// Draws the manifold, with the rays entering each of the already visited layers.
+    fn to_frame(&self, visited_layers: &[Vec<bool>]) -> Frame {
+        let mut frame = Frame::new(self.width, self.depth);
+
+        for y in 0..self.depth {
+            let rays = visited_layers.get(y);
+            for x in 0..self.width {
+                let has_ray = rays.is_some_and(|rays| rays[x]);
+
+                if (x, y) == self.start_position {
+                    frame.set(x, y, 'S', Style::fg(Color::Green).bold());
+                } else if self.is_splitter(x, y) {
+                    match has_ray {
+                        true => frame.set(x, y, '^', Style::fg(Color::Yellow).bold()),
+                        false => frame.set(x, y, '^', Style::fg(Color::BrightBlack)),
+                    }
+                } else if has_ray {
+                    frame.set(x, y, '|', Style::fg(Color::Cyan));
+                } else {
+                    frame.set(x, y, '.', Style::fg(Color::BrightBlack));
+                }
+            }
+        }
+
+        frame
     }
 }
 
@@ -123,10 +157,103 @@ impl DayImpl<Data> for Day<CURRENT_DAY> {
     }
 
     fn one(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.count_splits() as u64)
+        Answer::from(data.count_splits())
     }
 
     fn two(&self, data: &mut Data) -> Answer {
-        Answer::Number(data.count_paths() as u64)
+        Answer::from(data.count_paths())
+    }
+
+    fn validate(input: &str) -> Option<Vec<InputProblem>> {
+        let mut problems = Vec::new();
+        let mut starts = 0;
+
+        for (i, line) in input.lines().enumerate() {
+            if let Some(c) = line.chars().find(|c| !matches!(c, '.' | '^' | 'S')) {
+                problems.push(InputProblem::at(
+                    i,
+                    format!("unexpected character `{}`, expected `.`, `^` or `S`", c),
+                ));
+            }
+            if line.starts_with('^') || line.ends_with('^') {
+                problems.push(InputProblem::at(
+                    i,
+                    "splitter at the edge of the manifold",
+                ));
+            }
+            starts += line.matches('S').count();
+        }
+
+        match starts {
+            0 => problems.push(InputProblem::general("no start position `S`")),
+            1 => {}
+            n => problems.push(InputProblem::general(format!(
+                "{} start positions `S`, expected one",
+                n
+            ))),
+        }
+
+        Some(problems)
+    }
+
+    /// Generates a manifold `size` columns wide, with splitters on every other line.
+    ///
+    /// Like in the real input, splitters are placed in a triangle below the start, where rays can reach them.
+    /// The depth is limited to 250 lines, so the number of paths fits into 128 bits.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let width = size.max(3);
+        let depth = size.clamp(2, 250);
+        let start = width / 2;
+
+        let lines: Vec<String> = (0..depth)
+            .map(|y| {
+                let spread = y / 2;
+                (0..width)
+                    .map(|x| {
+                        let offset = x.abs_diff(start);
+                        if y == 0 && x == start {
+                            'S'
+                        } else if y % 2 == 0
+                            && offset < spread
+                            && offset % 2 != spread % 2
+                            && x != 0
+                            && x != width - 1
+                            && rng.chance(0.7)
+                        {
+                            '^'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Some(lines.join("\n"))
+    }
+
+    /// Shows the beams passing through the manifold, one layer at a time.
+    fn visualize(&self, data: &mut Data, emit: &mut dyn FnMut(Frame)) -> bool {
+        let mut splits = 0;
+        let mut rays = vec![false; data.width];
+        let mut visited_layers = Vec::with_capacity(data.depth);
+
+        rays[data.start_position.0] = true;
+
+        for layer in 0..data.depth {
+            visited_layers.push(rays.clone());
+
+            let (new_splits, new_rays) = data.perform_splits(rays, layer);
+            rays = new_rays;
+            splits += new_splits;
+
+            emit(data.to_frame(&visited_layers).with_caption(format!(
+                "Layer {}: {} splits so far",
+                layer + 1,
+                splits
+            )));
+        }
+
+        true
     }
 }
@@ -1,5 +1,9 @@
+use crate::visualize::Frame;
+use crate::{Phase, ProfileLimit, trace_span};
 use aoc_macro::mod_days;
+use std::hint::black_box;
 use std::time::{Duration, Instant};
+use utils::Rng;
 
 pub mod utils;
 
@@ -8,15 +12,32 @@ pub mod utils;
 
 pub struct Day<const DAY: u8>;
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Answer {
     Number(u64),
+    Signed(i64),
+    BigNumber(u128),
+    BigSigned(i128),
     String(String),
     Bitmap(Vec<Vec<bool>>),
+    /// Multiple values, displayed separated by commas.
+    List(Vec<Answer>),
 }
 
 impl Answer {
+    /// Returns the sign and magnitude of integer answers, so they can be compared regardless of their variant.
+    ///
+    /// Zero is never negative, so it has a single representation.
+    fn as_integer(&self) -> Option<(bool, u128)> {
+        match *self {
+            Self::Number(n) => Some((false, n as u128)),
+            Self::Signed(n) => Some((n < 0, n.unsigned_abs() as u128)),
+            Self::BigNumber(n) => Some((false, n)),
+            Self::BigSigned(n) => Some((n < 0, n.unsigned_abs())),
+            _ => None,
+        }
+    }
+
     fn append_per_line(str: String, prefix: &str) -> String {
         str.lines()
             .map(|v| prefix.to_owned() + v + "\n")
@@ -79,15 +100,43 @@ impl Answer {
     }
 }
 
+impl PartialEq for Answer {
+    /// Integer answers are equal if their values are, even if they use different variants.
+    fn eq(&self, other: &Self) -> bool {
+        if let (Some(a), Some(b)) = (self.as_integer(), other.as_integer()) {
+            return a == b;
+        }
+
+        match (self, other) {
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Bitmap(a), Self::Bitmap(b)) => a == b,
+            (Self::List(a), Self::List(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for Answer {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::Number(n) => write!(f, "{}", n),
+            Self::Signed(n) => write!(f, "{}", n),
+            Self::BigNumber(n) => write!(f, "{}", n),
+            Self::BigSigned(n) => write!(f, "{}", n),
             Self::String(s) => write!(f, "{}", s),
             Self::Bitmap(bm) => {
                 writeln!(f).unwrap();
                 write!(f, "{}", Self::minify_bitmap(bm))
             }
+            Self::List(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -98,12 +147,103 @@ impl From<u64> for Answer {
     }
 }
 
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Self::Number(n as u64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Self::Signed(n)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Self::Signed(n as i64)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        Self::BigNumber(n)
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(n: i128) -> Self {
+        Self::BigSigned(n)
+    }
+}
+
+impl<T> From<Vec<T>> for Answer
+where
+    T: Into<Answer>,
+{
+    fn from(values: Vec<T>) -> Self {
+        Self::List(values.into_iter().map(|v| v.into()).collect())
+    }
+}
+
 impl From<String> for Answer {
     fn from(s: String) -> Self {
         Self::String(s)
     }
 }
 
+/// A problem found while validating an input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputProblem {
+    /// The line the problem was found in, starting at 1. `None` if it concerns the whole input.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl InputProblem {
+    /// Creates a problem for the line with the specified index, starting at 0 as returned by [`Iterator::enumerate`].
+    pub fn at(index: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(index + 1),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a problem concerning the whole input.
+    pub fn general(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for InputProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Checks the parts of the input format shared by all days, like line endings.
+pub fn validate_common(input: &str) -> Vec<InputProblem> {
+    let mut problems = Vec::new();
+
+    if input.trim().is_empty() {
+        problems.push(InputProblem::general("the input is empty"));
+    }
+
+    for (i, line) in input.split('\n').enumerate() {
+        if line.ends_with('\r') {
+            problems.push(InputProblem::at(i, "line ends with CRLF instead of LF"));
+        }
+    }
+
+    problems
+}
+
 pub trait DayImpl<T>
 where
     T: Clone,
@@ -115,6 +255,11 @@ where
 
     fn expected_results() -> (Answer, Answer);
 
+    /// Further example inputs and their expected results, tested after the one from [`init_test`](Self::init_test).
+    fn extra_examples() -> Vec<(&'static str, (Answer, Answer))> {
+        Vec::new()
+    }
+
     /// Parse input
     fn init(input: &str) -> (Self, T)
     where
@@ -126,55 +271,135 @@ where
     /// Compute part 2
     fn two(&self, data: &mut T) -> Answer;
 
+    /// Compute part 1 using a slow, but simple reference implementation, if there is one.
+    ///
+    /// Used to cross-check the optimized implementation.
+    fn reference_one(&self, _data: &mut T) -> Option<Answer> {
+        None
+    }
+
+    /// Compute part 2 using a slow, but simple reference implementation, if there is one.
+    ///
+    /// Used to cross-check the optimized implementation.
+    fn reference_two(&self, _data: &mut T) -> Option<Answer> {
+        None
+    }
+
+    /// Check that an input matches the day's expected format, and return every problem found.
+    ///
+    /// Returns `None` if the day has no validator.
+    /// Common problems like CRLF line endings are checked by [`validate_common`] instead.
+    fn validate(_input: &str) -> Option<Vec<InputProblem>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Generate a random, structurally valid input, if the day supports it.
+    ///
+    /// `size` scales the input, its exact meaning depends on the day.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<String>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Solve the day, passing every step to `emit` as a frame, if the day supports visualization.
+    ///
+    /// Returns false if it doesn't.
+    fn visualize(&self, _data: &mut T, _emit: &mut dyn FnMut(Frame)) -> bool {
+        false
+    }
+
+    /// Compute a part, e.g. [`one`](Self::one), in a new memoization scope.
+    ///
+    /// Every solve goes through here, so results memoized for one input are never returned
+    /// for another, see [`crate::memo`].
+    fn solve<R>(&self, data: &mut T, part: impl FnOnce(&Self, &mut T) -> R) -> R
+    where
+        Self: Sized,
+    {
+        crate::memo::new_scope();
+        part(self, data)
+    }
+
+    /// Compute both parts using the optimized and the reference implementations.
+    ///
+    /// Returns `(optimized, reference)` for each part with a reference implementation.
+    fn crosscheck(&self, data: &T) -> [Option<(Answer, Answer)>; 2]
+    where
+        Self: Sized,
+    {
+        let one = self
+            .solve(&mut data.clone(), Self::reference_one)
+            .map(|reference| (self.solve(&mut data.clone(), Self::one), reference));
+        let two = self
+            .solve(&mut data.clone(), Self::reference_two)
+            .map(|reference| (self.solve(&mut data.clone(), Self::two), reference));
+
+        [one, two]
+    }
+
     /// Parse input and measure the time it took
     fn init_timed(input: &str) -> ((Self, T), Duration)
     where
         Self: Sized,
     {
+        let _span = trace_span!("phase", phase = "parse");
         let s = Instant::now();
         (Self::init(input), s.elapsed())
     }
 
     /// Compute part 1 and measure the time it took
-    fn one_timed(&self, data: &mut T) -> (Answer, Duration) {
+    fn one_timed(&self, data: &mut T) -> (Answer, Duration)
+    where
+        Self: Sized,
+    {
+        let _span = trace_span!("part", part = 1);
         let s = Instant::now();
-        (self.one(data), s.elapsed())
+        (self.solve(data, Self::one), s.elapsed())
     }
 
     /// Compute part 2 and measure the time it took
-    fn two_timed(&self, data: &mut T) -> (Answer, Duration) {
+    fn two_timed(&self, data: &mut T) -> (Answer, Duration)
+    where
+        Self: Sized,
+    {
+        let _span = trace_span!("part", part = 2);
         let s = Instant::now();
-        (self.two(data), s.elapsed())
+        (self.solve(data, Self::two), s.elapsed())
     }
 
     /// Compute both parts
-    #[allow(dead_code)]
     fn run(input: &str) -> (Answer, Answer)
     where
         Self: Sized,
     {
         let (day, mut data) = Self::init(input);
-        (day.one(&mut data.clone()), day.two(&mut data))
+        (
+            day.solve(&mut data.clone(), Self::one),
+            day.solve(&mut data, Self::two),
+        )
     }
 
     /// Init and compute part 1
-    #[allow(dead_code)]
     fn run_one(input: &str) -> Answer
     where
         Self: Sized,
     {
         let (day, mut data) = Self::init(input);
-        day.one(&mut data)
+        day.solve(&mut data, Self::one)
     }
 
     /// Init and compute part 1
-    #[allow(dead_code)]
     fn run_two(input: &str) -> Answer
     where
         Self: Sized,
     {
         let (day, mut data) = Self::init(input);
-        day.two(&mut data)
+        day.solve(&mut data, Self::two)
     }
 
     /// Init and compute part 1
@@ -209,30 +434,93 @@ where
         (one, two, i_t, one_t, two_t)
     }
 
-    /// Test part one
-    fn test_one() -> (bool, Answer, Answer)
+    /// Repeatedly run a single phase until the limit is reached.
+    ///
+    /// Fresh data is cloned before every iteration, outside of the measured region.
+    /// Returns the number of iterations and the total time spent inside the phase.
+    fn profile(input: &str, phase: Phase, limit: ProfileLimit) -> (u64, Duration)
     where
         Self: Sized,
     {
-        let (day, mut data) = Self::init_test();
-        let one = day.one(&mut data);
+        let (day, data) = Self::init(input);
+        let _span = trace_span!("profile", ?phase);
+        let start = Instant::now();
+        let mut iterations = 0;
+        let mut measured = Duration::ZERO;
+
+        while match limit {
+            ProfileLimit::Iterations(n) => iterations < n,
+            ProfileLimit::Duration(d) => start.elapsed() < d,
+        } {
+            match phase {
+                Phase::Parse => {
+                    let s = Instant::now();
+                    let result = black_box(Self::init(black_box(input)));
+                    measured += s.elapsed();
+                    drop(result);
+                }
+                Phase::One => {
+                    let mut data = data.clone();
+                    let s = Instant::now();
+                    let result = black_box(day.solve(black_box(&mut data), Self::one));
+                    measured += s.elapsed();
+                    drop(result);
+                }
+                Phase::Two => {
+                    let mut data = data.clone();
+                    let s = Instant::now();
+                    let result = black_box(day.solve(black_box(&mut data), Self::two));
+                    measured += s.elapsed();
+                    drop(result);
+                }
+            }
+            iterations += 1;
+        }
 
-        let (one_e, _) = Self::expected_results();
+        (iterations, measured)
+    }
 
-        (one_e == one, one, one_e)
+    /// The parsed example with the given 1-based index and its expected results
+    fn example(example: usize) -> Option<((Self, T), (Answer, Answer))>
+    where
+        Self: Sized,
+    {
+        match example {
+            0 => None,
+            1 => Some((Self::init_test(), Self::expected_results())),
+            _ => Self::extra_examples()
+                .into_iter()
+                .nth(example - 2)
+                .map(|(input, expected)| (Self::init(input), expected)),
+        }
     }
 
-    /// Test part two
-    fn test_two() -> (bool, Answer, Answer)
+    /// Test part one on the example with the given 1-based index, or `None` if there is no such example
+    fn test_one(example: usize) -> Option<(bool, Answer, Answer)>
     where
         Self: Sized,
     {
-        let (day, mut data) = Self::init_test();
-        let two = day.two(&mut data);
+        let ((day, mut data), (one_e, _)) = Self::example(example)?;
+        let one = {
+            let _span = trace_span!("part", part = 1, example);
+            day.solve(&mut data, Self::one)
+        };
 
-        let (_, two_e) = Self::expected_results();
+        Some((one_e == one, one, one_e))
+    }
 
-        (two_e == two, two, two_e)
+    /// Test part two on the example with the given 1-based index, or `None` if there is no such example
+    fn test_two(example: usize) -> Option<(bool, Answer, Answer)>
+    where
+        Self: Sized,
+    {
+        let ((day, mut data), (_, two_e)) = Self::example(example)?;
+        let two = {
+            let _span = trace_span!("part", part = 2, example);
+            day.solve(&mut data, Self::two)
+        };
+
+        Some((two_e == two, two, two_e))
     }
 
     /// Run both tests
@@ -241,8 +529,14 @@ where
         Self: Sized,
     {
         let (day, mut data) = Self::init_test();
-        let one = day.one(&mut data.clone());
-        let two = day.two(&mut data);
+        let one = {
+            let _span = trace_span!("part", part = 1);
+            day.solve(&mut data.clone(), Self::one)
+        };
+        let two = {
+            let _span = trace_span!("part", part = 2);
+            day.solve(&mut data, Self::two)
+        };
 
         let (one_e, two_e) = Self::expected_results();
 
@@ -251,3 +545,70 @@ where
 }
 
 mod_days!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_answers_compare_across_variants() {
+        assert_eq!(Answer::Number(5), Answer::Signed(5));
+        assert_eq!(Answer::Number(5), Answer::BigNumber(5));
+        assert_eq!(Answer::BigSigned(-7), Answer::Signed(-7));
+        assert_eq!(Answer::Signed(0), Answer::BigNumber(0));
+        assert_ne!(Answer::Signed(-5), Answer::Number(5));
+        assert_ne!(Answer::BigNumber(u64::MAX as u128 + 1), Answer::Number(0));
+        assert_eq!(Answer::BigSigned(i128::MIN), Answer::BigSigned(i128::MIN));
+        assert_ne!(Answer::Number(1), Answer::String("1".to_owned()));
+    }
+
+    #[test]
+    fn list_answers_compare_element_wise() {
+        assert_eq!(
+            Answer::List(vec![Answer::Number(1), Answer::Signed(-2)]),
+            Answer::List(vec![Answer::BigSigned(1), Answer::BigSigned(-2)])
+        );
+        assert_ne!(
+            Answer::List(vec![Answer::Number(1)]),
+            Answer::List(vec![Answer::Number(1), Answer::Number(2)])
+        );
+    }
+
+    #[test]
+    fn answers_are_displayed() {
+        assert_eq!(Answer::Signed(-12).to_string(), "-12");
+        assert_eq!(
+            Answer::BigNumber(u128::MAX).to_string(),
+            u128::MAX.to_string()
+        );
+        assert_eq!(
+            Answer::BigSigned(i128::MIN).to_string(),
+            i128::MIN.to_string()
+        );
+        assert_eq!(
+            Answer::List(vec![
+                Answer::Number(3),
+                Answer::String("a".to_owned()),
+                Answer::Signed(-1)
+            ])
+            .to_string(),
+            "3,a,-1"
+        );
+        assert_eq!(Answer::List(vec![]).to_string(), "");
+    }
+
+    #[test]
+    fn answers_from_primitives() {
+        assert!(matches!(Answer::from(3_u64), Answer::Number(3)));
+        assert!(matches!(Answer::from(3_usize), Answer::Number(3)));
+        assert!(matches!(Answer::from(-3_i64), Answer::Signed(-3)));
+        assert!(matches!(Answer::from(-3_isize), Answer::Signed(-3)));
+        assert!(matches!(Answer::from(3_u128), Answer::BigNumber(3)));
+        assert!(matches!(Answer::from(-3_i128), Answer::BigSigned(-3)));
+        assert!(matches!(Answer::from("x".to_owned()), Answer::String(s) if s == "x"));
+        assert_eq!(
+            Answer::from(vec![1_u64, 2]),
+            Answer::List(vec![Answer::Number(1), Answer::Number(2)])
+        );
+    }
+}
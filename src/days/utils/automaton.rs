@@ -0,0 +1,552 @@
+//! A cellular-automaton engine, applying a per-cell rule to a grid until nothing changes anymore.
+//!
+//! The rule gets the current value of a cell and the values of its neighbours, and returns the new value.
+//! Updates are either synchronous (every cell sees the state of the previous step, see [`Automaton::run`])
+//! or worklist-driven (only the neighbours of changed cells are visited again, and changes are visible
+//! immediately, see [`Automaton::run_worklist`]). The latter is faster, but only gives the same result
+//! for rules where the order of updates doesn't matter.
+
+use super::{Direction, Map, Point2, SparseGrid};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Which cells count as neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The 4 orthogonally adjacent cells.
+    VonNeumann,
+    /// The 8 orthogonally and diagonally adjacent cells.
+    Moore,
+}
+
+impl Neighbourhood {
+    fn directions(&self) -> &'static [Direction] {
+        match self {
+            Self::VonNeumann => &Direction::ALL,
+            Self::Moore => &Direction::ALL_WITH_DIAGONALS,
+        }
+    }
+}
+
+/// A grid which can be updated by an [`Automaton`].
+pub trait CellGrid {
+    type Position: Copy + Eq + Hash;
+    type Cell: Clone + PartialEq;
+
+    /// All positions which the rule is applied to.
+    fn positions(&self) -> Vec<Self::Position>;
+    fn cell(&self, position: Self::Position) -> Self::Cell;
+    fn set_cell(&mut self, position: Self::Position, cell: Self::Cell);
+    /// Appends the neighbours of a position to `out`.
+    fn neighbours(
+        &self,
+        position: Self::Position,
+        neighbourhood: Neighbourhood,
+        out: &mut Vec<Self::Position>,
+    );
+}
+
+impl<T> CellGrid for Map<T>
+where
+    T: Clone + PartialEq,
+{
+    type Position = (usize, usize);
+    type Cell = T;
+
+    fn positions(&self) -> Vec<(usize, usize)> {
+        Map::positions(self).collect()
+    }
+
+    fn cell(&self, position: (usize, usize)) -> T {
+        self.get(position.0, position.1).unwrap().clone()
+    }
+
+    fn set_cell(&mut self, position: (usize, usize), cell: T) {
+        self.set(position.0, position.1, cell);
+    }
+
+    fn neighbours(
+        &self,
+        position: (usize, usize),
+        neighbourhood: Neighbourhood,
+        out: &mut Vec<(usize, usize)>,
+    ) {
+        let (width, height) = self.dimensions();
+        let upper_limit = (width.saturating_sub(1), height.saturating_sub(1));
+        out.extend(
+            neighbourhood
+                .directions()
+                .iter()
+                .filter_map(|direction| direction.walk_pos(position, upper_limit)),
+        );
+    }
+}
+
+/// Empty cells are `None`. The rule is applied to the occupied cells and their neighbours,
+/// so the grid can grow in any direction.
+impl<T> CellGrid for SparseGrid<T>
+where
+    T: Clone + PartialEq,
+{
+    type Position = Point2<isize>;
+    type Cell = Option<T>;
+
+    fn positions(&self) -> Vec<Point2<isize>> {
+        let mut positions: HashSet<Point2<isize>> = self.positions().collect();
+        for position in self.positions() {
+            positions.extend(
+                Direction::ALL_WITH_DIAGONALS
+                    .iter()
+                    .map(|direction| position + *direction),
+            );
+        }
+        positions.into_iter().collect()
+    }
+
+    fn cell(&self, position: Point2<isize>) -> Option<T> {
+        self.get(position).cloned()
+    }
+
+    fn set_cell(&mut self, position: Point2<isize>, cell: Option<T>) {
+        match cell {
+            Some(cell) => {
+                self.insert(position, cell);
+            }
+            None => {
+                self.remove(position);
+            }
+        }
+    }
+
+    fn neighbours(
+        &self,
+        position: Point2<isize>,
+        neighbourhood: Neighbourhood,
+        out: &mut Vec<Point2<isize>>,
+    ) {
+        out.extend(
+            neighbourhood
+                .directions()
+                .iter()
+                .map(|direction| position + *direction),
+        );
+    }
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// A step didn't change any cell.
+    Fixpoint,
+    /// The grid returned to the state it had after step `start`, and repeats every `length` steps.
+    Cycle { start: usize, length: usize },
+    /// The maximum number of steps was reached.
+    StepLimit,
+}
+
+/// The result of a run of an [`Automaton`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunReport {
+    /// The number of cells changed by each step. A fixpoint ends with the step which changed nothing.
+    pub changes: Vec<usize>,
+    pub outcome: Outcome,
+}
+
+impl RunReport {
+    /// The number of changes made in total.
+    pub fn total_changes(&self) -> usize {
+        self.changes.iter().sum()
+    }
+}
+
+pub struct Automaton<G, R>
+where
+    G: CellGrid,
+    R: FnMut(&G::Cell, &[G::Cell]) -> G::Cell,
+{
+    grid: G,
+    neighbourhood: Neighbourhood,
+    rule: R,
+    steps: usize,
+}
+
+impl<G, R> Automaton<G, R>
+where
+    G: CellGrid,
+    R: FnMut(&G::Cell, &[G::Cell]) -> G::Cell,
+{
+    /// Creates an automaton, applying `rule` to the cells of `grid`.
+    ///
+    /// The rule gets the current value of a cell and the values of its neighbours, and returns its new value.
+    pub fn new(grid: G, neighbourhood: Neighbourhood, rule: R) -> Self {
+        Self {
+            grid,
+            neighbourhood,
+            rule,
+            steps: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &G {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> G {
+        self.grid
+    }
+
+    /// The number of steps performed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Applies the rule to a single cell, using the current state of the grid.
+    fn evaluate(
+        &mut self,
+        position: G::Position,
+        positions: &mut Vec<G::Position>,
+        cells: &mut Vec<G::Cell>,
+    ) -> Option<G::Cell> {
+        positions.clear();
+        self.grid
+            .neighbours(position, self.neighbourhood, positions);
+        cells.clear();
+        cells.extend(positions.iter().map(|&neighbour| self.grid.cell(neighbour)));
+
+        let current = self.grid.cell(position);
+        let new = (self.rule)(&current, cells);
+        (new != current).then_some(new)
+    }
+
+    /// Updates all cells at once, based on the state before the step. Returns the number of changed cells.
+    pub fn step(&mut self) -> usize {
+        let (mut positions, mut cells) = (Vec::new(), Vec::new());
+        let changes: Vec<(G::Position, G::Cell)> = self
+            .grid
+            .positions()
+            .into_iter()
+            .filter_map(|position| {
+                Some((
+                    position,
+                    self.evaluate(position, &mut positions, &mut cells)?,
+                ))
+            })
+            .collect();
+
+        self.steps += 1;
+        let changed = changes.len();
+        for (position, cell) in changes {
+            self.grid.set_cell(position, cell);
+        }
+        changed
+    }
+
+    /// Performs synchronous steps until one changes nothing, or until `max_steps` steps were performed.
+    pub fn run(&mut self, max_steps: Option<usize>) -> RunReport {
+        let mut changes = Vec::new();
+
+        loop {
+            if max_steps.is_some_and(|max| changes.len() >= max) {
+                return RunReport {
+                    changes,
+                    outcome: Outcome::StepLimit,
+                };
+            }
+
+            let changed = self.step();
+            changes.push(changed);
+            if changed == 0 {
+                return RunReport {
+                    changes,
+                    outcome: Outcome::Fixpoint,
+                };
+            }
+        }
+    }
+
+    /// Like [`Self::run`], but also stops once the grid repeats a previous state.
+    ///
+    /// Every state is stored to compare it with later ones, so this needs a grid which can be cloned,
+    /// hashed and compared.
+    pub fn run_detecting_cycles(&mut self, max_steps: Option<usize>) -> RunReport
+    where
+        G: Clone + Hash + Eq,
+    {
+        let mut seen = HashMap::from([(self.grid.clone(), 0)]);
+        let mut changes = Vec::new();
+
+        loop {
+            if max_steps.is_some_and(|max| changes.len() >= max) {
+                return RunReport {
+                    changes,
+                    outcome: Outcome::StepLimit,
+                };
+            }
+
+            let changed = self.step();
+            changes.push(changed);
+            if changed == 0 {
+                return RunReport {
+                    changes,
+                    outcome: Outcome::Fixpoint,
+                };
+            }
+
+            if let Some(start) = seen.insert(self.grid.clone(), changes.len()) {
+                return RunReport {
+                    outcome: Outcome::Cycle {
+                        start,
+                        length: changes.len() - start,
+                    },
+                    changes,
+                };
+            }
+        }
+    }
+
+    /// Updates cells one at a time until nothing changes anymore, revisiting only the neighbours of changed cells.
+    ///
+    /// Every step processes the cells queued by the previous one, starting with all cells.
+    /// Changes are visible to the following cells immediately. Stops after `max_steps` steps, if given.
+    pub fn run_worklist(&mut self, max_steps: Option<usize>) -> RunReport {
+        let (mut positions, mut cells) = (Vec::new(), Vec::new());
+        let mut queue: VecDeque<G::Position> = self.grid.positions().into();
+        let mut queued: HashSet<G::Position> = queue.iter().copied().collect();
+        let mut changes = Vec::new();
+
+        while !queue.is_empty() {
+            if max_steps.is_some_and(|max| changes.len() >= max) {
+                return RunReport {
+                    changes,
+                    outcome: Outcome::StepLimit,
+                };
+            }
+
+            let mut changed = 0;
+
+            for _ in 0..queue.len() {
+                let position = queue.pop_front().unwrap();
+                queued.remove(&position);
+
+                let Some(cell) = self.evaluate(position, &mut positions, &mut cells) else {
+                    continue;
+                };
+                self.grid.set_cell(position, cell);
+                changed += 1;
+
+                positions.clear();
+                self.grid
+                    .neighbours(position, self.neighbourhood, &mut positions);
+                for &neighbour in &positions {
+                    if queued.insert(neighbour) {
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            self.steps += 1;
+            changes.push(changed);
+        }
+
+        RunReport {
+            changes,
+            outcome: Outcome::Fixpoint,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type LifeRule = fn(&bool, &[bool]) -> bool;
+
+    fn life(cell: &bool, neighbours: &[bool]) -> bool {
+        let alive = neighbours.iter().filter(|v| **v).count();
+        alive == 3 || (*cell && alive == 2)
+    }
+
+    fn map(value: &str) -> Map<bool> {
+        Map::from_str_with(value, |c| c == '#')
+    }
+
+    /// A grid whose states all have the same hash, so only a real comparison can tell them apart.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Colliding(Map<u8>);
+
+    impl Hash for Colliding {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            0.hash(state);
+        }
+    }
+
+    impl CellGrid for Colliding {
+        type Position = (usize, usize);
+        type Cell = u8;
+
+        fn positions(&self) -> Vec<(usize, usize)> {
+            CellGrid::positions(&self.0)
+        }
+
+        fn cell(&self, position: (usize, usize)) -> u8 {
+            self.0.cell(position)
+        }
+
+        fn set_cell(&mut self, position: (usize, usize), cell: u8) {
+            self.0.set_cell(position, cell);
+        }
+
+        fn neighbours(
+            &self,
+            position: (usize, usize),
+            neighbourhood: Neighbourhood,
+            out: &mut Vec<(usize, usize)>,
+        ) {
+            self.0.neighbours(position, neighbourhood, out);
+        }
+    }
+
+    #[test]
+    fn synchronous_steps_see_the_previous_state() {
+        let mut automaton = Automaton::new(
+            map(".....\n..#..\n..#..\n..#..\n....."),
+            Neighbourhood::Moore,
+            life as LifeRule,
+        );
+        assert_eq!(automaton.step(), 4);
+        assert_eq!(automaton.grid(), &map(".....\n.....\n.###.\n.....\n....."));
+        assert_eq!(automaton.steps(), 1);
+    }
+
+    #[test]
+    fn runs_until_fixpoint_or_step_limit() {
+        // A block is stable, the lone cell dies.
+        let mut automaton = Automaton::new(
+            map("##...\n##...\n....#"),
+            Neighbourhood::Moore,
+            life as LifeRule,
+        );
+        let report = automaton.run(None);
+        assert_eq!(
+            report,
+            RunReport {
+                changes: vec![1, 0],
+                outcome: Outcome::Fixpoint
+            }
+        );
+        assert_eq!(report.total_changes(), 1);
+
+        let blinker = map(".....\n..#..\n..#..\n..#..\n.....");
+        let mut automaton = Automaton::new(blinker.clone(), Neighbourhood::Moore, life as LifeRule);
+        let report = automaton.run(Some(5));
+        assert_eq!(report.changes, vec![4; 5]);
+        assert_eq!(report.outcome, Outcome::StepLimit);
+        assert_eq!(automaton.steps(), 5);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let blinker = map(".....\n..#..\n..#..\n..#..\n.....");
+        let mut automaton = Automaton::new(blinker.clone(), Neighbourhood::Moore, life as LifeRule);
+        let report = automaton.run_detecting_cycles(None);
+        assert_eq!(
+            report.outcome,
+            Outcome::Cycle {
+                start: 0,
+                length: 2
+            }
+        );
+        assert_eq!(automaton.into_grid(), blinker);
+
+        // A counter which needs two steps to enter its cycle of length 3.
+        let counter = |&cell: &u8, _: &[u8]| if cell < 4 { cell + 1 } else { 2 };
+        let grid = Map::from(vec![vec![0_u8]]);
+        let mut automaton = Automaton::new(grid.clone(), Neighbourhood::VonNeumann, counter);
+        assert_eq!(
+            automaton.run_detecting_cycles(Some(4)).outcome,
+            Outcome::StepLimit
+        );
+        let mut automaton = Automaton::new(grid, Neighbourhood::VonNeumann, counter);
+        let report = automaton.run_detecting_cycles(None);
+        assert_eq!(
+            report.outcome,
+            Outcome::Cycle {
+                start: 2,
+                length: 3
+            }
+        );
+        assert_eq!(report.changes, vec![1; 5]);
+    }
+
+    #[test]
+    fn equal_hashes_are_not_mistaken_for_cycles() {
+        let grid = Colliding(Map::from(vec![vec![0_u8]]));
+        let mut automaton =
+            Automaton::new(grid, Neighbourhood::VonNeumann, |&cell: &u8, _: &[u8]| {
+                (cell + 1) % 3
+            });
+        let report = automaton.run_detecting_cycles(None);
+        assert_eq!(
+            report.outcome,
+            Outcome::Cycle {
+                start: 0,
+                length: 3
+            }
+        );
+    }
+
+    #[test]
+    fn worklist_matches_synchronous_steps_for_order_independent_rules() {
+        // Removes cells with less than 4 neighbours, like day 4.
+        let rule = |cell: &bool, neighbours: &[bool]| {
+            *cell && neighbours.iter().filter(|v| **v).count() >= 4
+        };
+        let grid = map("..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@\n@.@@@@..@.\n@@.@@@@.@@\n.@@@@@@@.@\n.@.@.@.@@@\n@.@@@.@@@@\n.@@@@@@@@.\n@.@.@@@.@.".replace('@', "#").as_str());
+
+        let mut synchronous = Automaton::new(grid.clone(), Neighbourhood::Moore, rule);
+        let mut worklist = Automaton::new(grid, Neighbourhood::Moore, rule);
+        let synchronous_report = synchronous.run(None);
+        let worklist_report = worklist.run_worklist(None);
+
+        assert_eq!(synchronous_report.total_changes(), 43);
+        assert_eq!(worklist_report.total_changes(), 43);
+        assert_eq!(worklist_report.outcome, Outcome::Fixpoint);
+        assert_eq!(synchronous.grid(), worklist.grid());
+    }
+
+    #[test]
+    fn worklist_stops_at_step_limit() {
+        // Cells die next to a dead cell, which spreads one cell to the left per step.
+        let rule = |cell: &bool, neighbours: &[bool]| *cell && neighbours.iter().all(|v| *v);
+        let mut automaton = Automaton::new(map("####."), Neighbourhood::VonNeumann, rule);
+        let report = automaton.run_worklist(Some(2));
+        assert_eq!(report.outcome, Outcome::StepLimit);
+        assert_eq!(report.changes, vec![1, 1]);
+        assert_eq!(automaton.grid(), &map("##..."));
+
+        assert_eq!(automaton.run_worklist(None).outcome, Outcome::Fixpoint);
+        assert_eq!(automaton.grid(), &map("....."));
+    }
+
+    #[test]
+    fn sparse_grids_grow() {
+        let glider = SparseGrid::from_str_with(".#.\n..#\n###", |c| (c == '#').then_some(()));
+        let rule = |cell: &Option<()>, neighbours: &[Option<()>]| {
+            let alive = neighbours.iter().flatten().count();
+            (alive == 3 || (cell.is_some() && alive == 2)).then_some(())
+        };
+        let mut automaton = Automaton::new(glider.clone(), Neighbourhood::Moore, rule);
+        automaton.run(Some(4));
+
+        // After 4 steps, the glider moved one cell right and down.
+        let mut moved = SparseGrid::new();
+        for point in glider.positions() {
+            moved.insert(point + Direction::SouthEast, ());
+        }
+        assert_eq!(automaton.grid(), &moved);
+        assert_eq!(
+            automaton.grid().bounds(),
+            Some((Point2::new(0, 0), Point2::new(3, 3)))
+        );
+    }
+}
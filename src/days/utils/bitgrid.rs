@@ -0,0 +1,360 @@
+//! A grid of booleans packed into 64-bit words, for cellular-automaton style puzzles.
+//!
+//! Neighbours are counted for 64 cells at once: the eight neighbour masks of a word are added
+//! using bit-sliced counters, so bit `i` of plane `n` holds bit `n` of the count of cell `i`.
+
+use super::Map;
+
+const WORD_BITS: usize = 64;
+
+/// The number of set neighbours of 64 adjacent cells, stored as bit-sliced 4-bit counters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NeighbourCounts([u64; 4]);
+
+impl NeighbourCounts {
+    /// Adds a mask of neighbours to the counters, using a chain of half adders.
+    fn add(&mut self, mut carry: u64) {
+        for plane in &mut self.0 {
+            let sum = *plane ^ carry;
+            carry &= *plane;
+            *plane = sum;
+        }
+    }
+
+    /// The cells with exactly `count` set neighbours.
+    pub fn equal(&self, count: u8) -> u64 {
+        self.0
+            .iter()
+            .enumerate()
+            .fold(!0, |mask, (bit, plane)| match count >> bit & 1 {
+                1 => mask & plane,
+                _ => mask & !plane,
+            })
+    }
+
+    /// The cells with at least `count` set neighbours.
+    pub fn at_least(&self, count: u8) -> u64 {
+        (count..=8).fold(0, |mask, v| mask | self.equal(v))
+    }
+
+    /// The cells with less than `count` set neighbours.
+    pub fn less_than(&self, count: u8) -> u64 {
+        !self.at_least(count)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    words_per_row: usize,
+    width: usize,
+    height: usize,
+}
+
+impl BitGrid {
+    /// Creates a grid of the specified dimensions, with every cell unset.
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        Self {
+            words: vec![0; words_per_row * height],
+            words_per_row,
+            width,
+            height,
+        }
+    }
+
+    /// Parses a multi-line &str, setting the cells for which `f` returns true.
+    pub fn from_str_with(value: &str, mut f: impl FnMut(char) -> bool) -> Self {
+        let width = value.lines().map(|v| v.chars().count()).max().unwrap_or(0);
+        let height = value.lines().count();
+        let mut grid = Self::new(width, height);
+
+        for (y, line) in value.lines().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                if f(char) {
+                    grid.set(x, y, true);
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Converts a [`Map`], setting the cells for which `f` returns true.
+    pub fn from_map<T>(map: &Map<T>, mut f: impl FnMut(&T) -> bool) -> Self {
+        let (width, height) = map.dimensions();
+        let mut grid = Self::new(width, height);
+        for ((x, y), tile) in map.iter() {
+            if f(tile) {
+                grid.set(x, y, true);
+            }
+        }
+        grid
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Gets a cell. Positions outside the grid are unset.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width
+            && y < self.height
+            && self.words[y * self.words_per_row + x / WORD_BITS] >> (x % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(x < self.width && y < self.height, "Position out of bounds.");
+
+        let word = &mut self.words[y * self.words_per_row + x / WORD_BITS];
+        match value {
+            true => *word |= 1 << (x % WORD_BITS),
+            false => *word &= !(1 << (x % WORD_BITS)),
+        }
+    }
+
+    /// The number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|v| v.count_ones() as usize).sum()
+    }
+
+    /// Iterates over the positions of all set cells, line by line.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, first_x) = (i / self.words_per_row, i % self.words_per_row * WORD_BITS);
+            std::iter::successors((word != 0).then_some(word), |v| {
+                let rest = v & (v - 1);
+                (rest != 0).then_some(rest)
+            })
+            .map(move |v| (first_x + v.trailing_zeros() as usize, y))
+        })
+    }
+
+    /// The bits of a row which are part of the grid.
+    fn valid_mask(&self, word_index: usize) -> u64 {
+        let remaining = self.width - word_index * WORD_BITS;
+        if remaining >= WORD_BITS {
+            !0
+        } else {
+            (1 << remaining) - 1
+        }
+    }
+
+    /// The word of a row, or 0 for rows and words outside the grid.
+    fn word(&self, y: Option<usize>, word_index: Option<usize>) -> u64 {
+        match (y, word_index) {
+            (Some(y), Some(w)) if y < self.height && w < self.words_per_row => {
+                self.words[y * self.words_per_row + w]
+            }
+            _ => 0,
+        }
+    }
+
+    /// Counts the set neighbours (including diagonals) of the 64 cells in a word.
+    fn neighbour_counts(&self, y: usize, word_index: usize) -> NeighbourCounts {
+        let mut counts = NeighbourCounts::default();
+
+        for (dy, include_center) in [(-1, true), (0, false), (1, true)] {
+            let row = y.checked_add_signed(dy);
+            let center = self.word(row, Some(word_index));
+            let before = self.word(row, word_index.checked_sub(1));
+            let after = self.word(row, Some(word_index + 1));
+
+            // the neighbours to the left of each cell, and to the right of each cell.
+            counts.add(center << 1 | before >> (WORD_BITS - 1));
+            counts.add(center >> 1 | after << (WORD_BITS - 1));
+            if include_center {
+                counts.add(center);
+            }
+        }
+
+        counts
+    }
+
+    /// Counts the cells selected by `rule`, which is applied to every word of cells and their neighbour counts.
+    pub fn count_where(&self, mut rule: impl FnMut(u64, &NeighbourCounts) -> u64) -> usize {
+        let mut count = 0;
+        for y in 0..self.height {
+            for w in 0..self.words_per_row {
+                let cells = self.words[y * self.words_per_row + w];
+                let selected = rule(cells, &self.neighbour_counts(y, w)) & self.valid_mask(w);
+                count += selected.count_ones() as usize;
+            }
+        }
+        count
+    }
+
+    /// Updates all cells at once, replacing every word with the result of `rule`
+    /// applied to its cells and their neighbour counts.
+    ///
+    /// Returns the number of cells which changed.
+    pub fn update(&mut self, mut rule: impl FnMut(u64, &NeighbourCounts) -> u64) -> usize {
+        // rows are written back once the row below has been counted, as it still needs the old values.
+        let mut previous_row: Option<Vec<u64>> = None;
+        let mut current_row = vec![0; self.words_per_row];
+        let mut changed = 0;
+
+        for y in 0..self.height {
+            for (w, new) in current_row.iter_mut().enumerate() {
+                let cells = self.words[y * self.words_per_row + w];
+                *new = rule(cells, &self.neighbour_counts(y, w)) & self.valid_mask(w);
+                changed += (*new ^ cells).count_ones() as usize;
+            }
+
+            if let Some(row) = previous_row.as_mut() {
+                let start = (y - 1) * self.words_per_row;
+                self.words[start..start + self.words_per_row].copy_from_slice(row);
+                std::mem::swap(row, &mut current_row);
+            } else {
+                previous_row = Some(std::mem::replace(
+                    &mut current_row,
+                    vec![0; self.words_per_row],
+                ));
+            }
+        }
+
+        if let Some(row) = previous_row {
+            let start = (self.height - 1) * self.words_per_row;
+            self.words[start..start + self.words_per_row].copy_from_slice(&row);
+        }
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::utils::Rng;
+
+    /// A random grid spanning several words per row, with a partial last word.
+    fn random_grid(seed: u64) -> BitGrid {
+        let mut rng = Rng::new(seed);
+        let mut grid = BitGrid::new(150, 7);
+        for y in 0..7 {
+            for x in 0..150 {
+                grid.set(x, y, rng.chance(0.4));
+            }
+        }
+        grid
+    }
+
+    fn naive_neighbours(grid: &BitGrid, x: usize, y: usize) -> u8 {
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0)
+                    && let (Some(nx), Some(ny)) =
+                        (x.checked_add_signed(dx), y.checked_add_signed(dy))
+                {
+                    count += grid.get(nx, ny) as u8;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn bit_sliced_counters() {
+        let mut counts = NeighbourCounts::default();
+        // Bit 0 gets 8 neighbours, bit 1 gets 3 and bit 2 none.
+        for i in 0..8 {
+            counts.add(if i < 3 { 0b011 } else { 0b001 });
+        }
+        assert_eq!(counts.equal(8) & 0b111, 0b001);
+        assert_eq!(counts.equal(3) & 0b111, 0b010);
+        assert_eq!(counts.equal(0) & 0b111, 0b100);
+        assert_eq!(counts.at_least(3) & 0b111, 0b011);
+        assert_eq!(counts.less_than(4) & 0b111, 0b110);
+    }
+
+    #[test]
+    fn cells_and_conversions() {
+        let grid = BitGrid::from_str_with("#..\n.#\n..#", |c| c == '#');
+        assert_eq!(grid.dimensions(), (3, 3));
+        assert!(grid.get(0, 0) && grid.get(1, 1) && grid.get(2, 2));
+        assert!(!grid.get(2, 1));
+        assert!(!grid.get(3, 0) && !grid.get(0, 3));
+        assert_eq!(grid.count_ones(), 3);
+        assert_eq!(
+            grid.iter_ones().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (2, 2)]
+        );
+
+        let map = Map::from_str_with("#..\n.#.\n..#", |c| c);
+        assert_eq!(BitGrid::from_map(&map, |&c| c == '#'), grid);
+
+        let mut grid = grid;
+        grid.set(0, 0, false);
+        grid.set(2, 1, true);
+        assert_eq!(
+            grid.iter_ones().collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1), (2, 2)]
+        );
+        assert_eq!(BitGrid::new(0, 0).iter_ones().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Position out of bounds.")]
+    fn setting_outside_panics() {
+        BitGrid::new(3, 3).set(3, 0, true);
+    }
+
+    #[test]
+    fn neighbour_counts_match_naive_counting() {
+        let grid = random_grid(7);
+        for count in 0..=8 {
+            let expected = (0..7)
+                .flat_map(|y| (0..150).map(move |x| (x, y)))
+                .filter(|&(x, y)| naive_neighbours(&grid, x, y) == count)
+                .count();
+            assert_eq!(
+                grid.count_where(|_, n| n.equal(count)),
+                expected,
+                "count {}",
+                count
+            );
+        }
+        // Bits beyond the width never count.
+        assert_eq!(grid.count_where(|_, _| !0), 150 * 7);
+    }
+
+    #[test]
+    fn update_applies_the_rule_to_all_cells_at_once() {
+        let mut grid = random_grid(11);
+        let mut expected = grid.clone();
+        for y in 0..7 {
+            for x in 0..150 {
+                let n = naive_neighbours(&grid, x, y);
+                expected.set(x, y, n == 3 || (grid.get(x, y) && n == 2));
+            }
+        }
+        let changed = (0..7)
+            .flat_map(|y| (0..150).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid.get(x, y) != expected.get(x, y))
+            .count();
+
+        assert_eq!(
+            grid.update(|cells, n| n.equal(3) | (cells & n.equal(2))),
+            changed
+        );
+        assert_eq!(grid, expected);
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut grid = BitGrid::from_str_with(".....\n..#..\n..#..\n..#..\n.....", |c| c == '#');
+        let life = |cells: u64, n: &NeighbourCounts| n.equal(3) | (cells & n.equal(2));
+
+        assert_eq!(grid.update(life), 4);
+        assert_eq!(
+            grid.iter_ones().collect::<Vec<_>>(),
+            vec![(1, 2), (2, 2), (3, 2)]
+        );
+        assert_eq!(grid.update(life), 4);
+        assert_eq!(
+            grid.iter_ones().collect::<Vec<_>>(),
+            vec![(2, 1), (2, 2), (2, 3)]
+        );
+    }
+}
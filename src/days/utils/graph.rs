@@ -0,0 +1,666 @@
+//! Graphs stored as adjacency lists, with nodes identified by any hashable label.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, u64)>>,
+    directed: bool,
+}
+
+impl<N> Graph<N>
+where
+    N: Eq + Hash + Clone,
+{
+    fn new(directed: bool) -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+            directed,
+        }
+    }
+
+    /// Creates an empty graph, whose edges only lead from their first node to the second.
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    /// Creates an empty graph, whose edges lead both ways.
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Adds a node if it doesn't exist yet, and returns its index.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+
+        self.indices.insert(node.clone(), self.nodes.len());
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    /// Adds an edge with a weight of 1, adding the nodes if they don't exist yet.
+    pub fn add_edge(&mut self, from: N, to: N) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    /// Adds a weighted edge, adding the nodes if they don't exist yet.
+    pub fn add_weighted_edge(&mut self, from: N, to: N, weight: u64) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.edges[from].push((to, weight));
+        if !self.directed && from != to {
+            self.edges[to].push((from, weight));
+        }
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// All nodes, in the order they were added.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    /// The nodes reachable using a single edge, and the weights of these edges.
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = (&N, u64)> {
+        self.indices
+            .get(node)
+            .into_iter()
+            .flat_map(|&i| self.edges[i].iter())
+            .map(|&(to, weight)| (&self.nodes[to], weight))
+    }
+
+    /// All edges as `(from, to, weight)`. Edges of undirected graphs are only included once.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N, u64)> {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges
+                    .iter()
+                    .filter(move |(to, _)| self.directed || from <= *to)
+                    .map(move |&(to, weight)| (&self.nodes[from], &self.nodes[to], weight))
+            })
+    }
+
+    /// The number of edges, ignoring weights, from every node to every other node it can reach.
+    /// Returns an empty map if the start node doesn't exist.
+    pub fn bfs(&self, start: &N) -> HashMap<&N, usize> {
+        let mut distances = HashMap::new();
+        let Some(&start) = self.indices.get(start) else {
+            return distances;
+        };
+
+        let mut visited = vec![false; self.len()];
+        let mut queue = VecDeque::from([(start, 0)]);
+        visited[start] = true;
+
+        while let Some((node, distance)) = queue.pop_front() {
+            distances.insert(&self.nodes[node], distance);
+
+            for &(next, _) in &self.edges[node] {
+                if !visited[next] {
+                    visited[next] = true;
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// The nodes reachable from the start node, in depth-first pre-order.
+    pub fn dfs(&self, start: &N) -> Vec<&N> {
+        let mut order = Vec::new();
+        let Some(&start) = self.indices.get(start) else {
+            return order;
+        };
+
+        let mut visited = vec![false; self.len()];
+        let mut stack = vec![start];
+
+        while let Some(node) = stack.pop() {
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            order.push(&self.nodes[node]);
+
+            // pushed in reverse, so the first edge is visited first.
+            stack.extend(
+                self.edges[node]
+                    .iter()
+                    .rev()
+                    .map(|&(next, _)| next)
+                    .filter(|&next| !visited[next]),
+            );
+        }
+
+        order
+    }
+
+    /// The node indices of a directed graph in topological order, or None if it has a cycle.
+    fn topological_indices(&self) -> Option<Vec<usize>> {
+        let mut incoming = vec![0; self.len()];
+        for edges in &self.edges {
+            for &(to, _) in edges {
+                incoming[to] += 1;
+            }
+        }
+
+        let mut queue: VecDeque<usize> = (0..self.len()).filter(|&i| incoming[i] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &(to, _) in &self.edges[node] {
+                incoming[to] -= 1;
+                if incoming[to] == 0 {
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Orders the nodes of a directed graph so every edge leads to a later node.
+    ///
+    /// Returns None if the graph has a cycle, or is undirected.
+    pub fn topological_sort(&self) -> Option<Vec<&N>> {
+        if !self.directed {
+            return None;
+        }
+
+        Some(
+            self.topological_indices()?
+                .into_iter()
+                .map(|i| &self.nodes[i])
+                .collect(),
+        )
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        if self.directed {
+            return self.topological_indices().is_none();
+        }
+
+        let mut sets = UnionFind::new(self.len());
+        self.edges.iter().enumerate().any(|(from, edges)| {
+            edges
+                .iter()
+                .filter(|&&(to, _)| from <= to)
+                .any(|&(to, _)| !sets.union(from, to))
+        })
+    }
+
+    /// Groups the nodes into strongly connected components, using Tarjan's algorithm.
+    ///
+    /// Components are returned in reverse topological order: no edge leads to an earlier component.
+    /// For undirected graphs, these are the connected components.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&N>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let mut index = vec![UNVISITED; self.len()];
+        let mut low_link = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..self.len() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+
+            // (node, position of the next edge to visit), replacing recursion.
+            let mut call_stack = vec![(root, 0)];
+            while let Some(&mut (node, ref mut edge)) = call_stack.last_mut() {
+                if *edge == 0 {
+                    index[node] = next_index;
+                    low_link[node] = next_index;
+                    next_index += 1;
+                    stack.push(node);
+                    on_stack[node] = true;
+                }
+
+                if let Some(&(next, _)) = self.edges[node].get(*edge) {
+                    *edge += 1;
+                    if index[next] == UNVISITED {
+                        call_stack.push((next, 0));
+                    } else if on_stack[next] {
+                        low_link[node] = low_link[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    low_link[parent] = low_link[parent].min(low_link[node]);
+                }
+
+                if low_link[node] == index[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(&self.nodes[member]);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Finds a minimum spanning tree (or forest) of an undirected graph, using Kruskal's algorithm.
+    ///
+    /// Returns the total weight and the chosen edges. Panics if the graph is directed.
+    pub fn minimum_spanning_tree(&self) -> (u64, Vec<(&N, &N, u64)>) {
+        assert!(
+            !self.directed,
+            "Minimum spanning trees are only defined for undirected graphs."
+        );
+
+        let mut edges: Vec<(usize, usize, u64)> = self
+            .edges
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |&(to, weight)| (from, to, weight)))
+            .filter(|(from, to, _)| from < to)
+            .collect();
+        edges.sort_by_key(|&(_, _, weight)| weight);
+
+        let mut sets = UnionFind::new(self.len());
+        let mut total = 0;
+        let mut tree = Vec::new();
+
+        for (from, to, weight) in edges {
+            if sets.union(from, to) {
+                total += weight;
+                tree.push((&self.nodes[from], &self.nodes[to], weight));
+            }
+        }
+
+        (total, tree)
+    }
+
+    /// Counts the paths from every node to `to` in a directed acyclic graph.
+    ///
+    /// Every node's count is the sum of the counts of its successors, which are memoized
+    /// by visiting the nodes in reverse topological order. Counts which don't fit into a u128 are None.
+    fn path_counts_to(&self, order: &[usize], to: usize) -> Vec<Option<u128>> {
+        let mut counts = vec![Some(0); self.len()];
+        counts[to] = Some(1);
+
+        for &node in order.iter().rev() {
+            if node != to {
+                counts[node] = self.edges[node]
+                    .iter()
+                    .try_fold(0_u128, |sum, &(next, _)| sum.checked_add(counts[next]?));
+            }
+        }
+
+        counts
+    }
+
+    /// Counts the different paths from `from` to `to` in a directed acyclic graph.
+    ///
+    /// Returns None if the graph has a cycle, or is undirected, as there may be infinitely many paths.
+    /// Also returns None if the number of paths doesn't fit into a u128.
+    pub fn count_paths(&self, from: &N, to: &N) -> Option<u128> {
+        self.count_paths_through(from, to, &[])
+    }
+
+    /// Counts the different paths from `from` to `to` in a directed acyclic graph,
+    /// which visit all `waypoints` in any order.
+    ///
+    /// Returns None if the graph has a cycle, or is undirected, as there may be infinitely many paths.
+    /// Also returns None if the number of paths doesn't fit into a u128.
+    pub fn count_paths_through(&self, from: &N, to: &N, waypoints: &[N]) -> Option<u128> {
+        if !self.directed {
+            return None;
+        }
+        let order = self.topological_indices()?;
+
+        let mut stops = Vec::with_capacity(waypoints.len() + 2);
+        for node in std::iter::once(from).chain(waypoints).chain([to]) {
+            match self.indices.get(node) {
+                Some(&i) => stops.push(i),
+                None => return Some(0),
+            }
+        }
+
+        // a path visits its nodes in topological order, so there is only one possible order of the waypoints.
+        let mut position = vec![0; self.len()];
+        for (i, &node) in order.iter().enumerate() {
+            position[node] = i;
+        }
+        stops[1..=waypoints.len()].sort_by_key(|&node| position[node]);
+
+        let segments: Vec<Option<u128>> = stops
+            .windows(2)
+            .map(|segment| self.path_counts_to(&order, segment[1])[segment[0]])
+            .collect();
+
+        // a segment without paths leaves no paths at all, even if another segment has too many to count.
+        if segments.contains(&Some(0)) {
+            return Some(0);
+        }
+        segments
+            .into_iter()
+            .try_fold(1_u128, |total, count| total.checked_mul(count?))
+    }
+}
+
+/// Disjoint sets of the numbers `0..len`, with path compression and union by size.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// Creates `len` sets, each containing a single number.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// The representative of the set containing `value`.
+    pub fn find(&mut self, value: usize) -> usize {
+        let mut root = value;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut current = value;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns false if they already were the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (larger, smaller) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+        self.count -= 1;
+
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The size of the set containing `value`.
+    pub fn set_size(&mut self, value: usize) -> usize {
+        let root = self.find(value);
+        self.sizes[root]
+    }
+
+    /// The number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directed(edges: &[(&'static str, &'static str)]) -> Graph<&'static str> {
+        let mut graph = Graph::directed();
+        for &(from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    /// A chain of `len` diamonds, each doubling the number of paths from start to end.
+    fn diamonds(len: usize) -> Graph<usize> {
+        let mut graph = Graph::directed();
+        for i in 0..len {
+            let (start, end) = (3 * i, 3 * i + 3);
+            graph.add_edge(start, start + 1);
+            graph.add_edge(start, start + 2);
+            graph.add_edge(start + 1, end);
+            graph.add_edge(start + 2, end);
+        }
+        graph
+    }
+
+    #[test]
+    fn nodes_and_edges() {
+        let mut graph = Graph::undirected();
+        assert_eq!(graph.add_node('a'), 0);
+        graph.add_weighted_edge('a', 'b', 3);
+        graph.add_edge('b', 'c');
+        graph.add_edge('c', 'c');
+        assert_eq!(graph.add_node('b'), 1);
+
+        assert_eq!(graph.len(), 3);
+        assert!(graph.contains(&'c') && !graph.contains(&'d'));
+        assert_eq!(graph.nodes(), &['a', 'b', 'c']);
+        assert_eq!(
+            graph.neighbours(&'b').collect::<Vec<_>>(),
+            vec![(&'a', 3), (&'c', 1)]
+        );
+        assert_eq!(graph.neighbours(&'d').count(), 0);
+        // Undirected edges are listed once, self loops included.
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            vec![(&'a', &'b', 3), (&'b', &'c', 1), (&'c', &'c', 1)]
+        );
+    }
+
+    #[test]
+    fn traversals() {
+        let graph = directed(&[
+            ("a", "b"),
+            ("a", "c"),
+            ("b", "d"),
+            ("c", "d"),
+            ("d", "e"),
+            ("x", "a"),
+        ]);
+        let distances = graph.bfs(&"a");
+        assert_eq!(distances.len(), 5);
+        assert_eq!(distances[&"d"], 2);
+        assert_eq!(distances[&"e"], 3);
+        assert!(!distances.contains_key(&"x"));
+        assert!(graph.bfs(&"missing").is_empty());
+
+        assert_eq!(graph.dfs(&"a"), vec![&"a", &"b", &"d", &"e", &"c"]);
+        assert!(graph.dfs(&"missing").is_empty());
+    }
+
+    #[test]
+    fn topological_order_and_cycles() {
+        let graph = directed(&[
+            ("shirt", "tie"),
+            ("tie", "jacket"),
+            ("trousers", "shoes"),
+            ("trousers", "belt"),
+            ("belt", "jacket"),
+        ]);
+        let order = graph.topological_sort().unwrap();
+        let position = |node| order.iter().position(|v| **v == node).unwrap();
+        for (from, to, _) in graph.edges() {
+            assert!(position(*from) < position(*to));
+        }
+        assert!(!graph.has_cycle());
+
+        let cyclic = directed(&[("a", "b"), ("b", "c"), ("c", "a")]);
+        assert!(cyclic.has_cycle());
+        assert_eq!(cyclic.topological_sort(), None);
+
+        let mut tree = Graph::undirected();
+        tree.add_edge(1, 2);
+        tree.add_edge(2, 3);
+        assert!(!tree.has_cycle());
+        assert_eq!(tree.topological_sort(), None);
+        tree.add_edge(3, 1);
+        assert!(tree.has_cycle());
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let graph = directed(&[
+            ("a", "b"),
+            ("b", "a"),
+            ("b", "c"),
+            ("c", "d"),
+            ("d", "c"),
+            ("d", "e"),
+        ]);
+        let components: Vec<Vec<&str>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|component| {
+                let mut component: Vec<&str> = component.into_iter().copied().collect();
+                component.sort();
+                component
+            })
+            .collect();
+        // Reverse topological order: sinks first.
+        assert_eq!(components, vec![vec!["e"], vec!["c", "d"], vec!["a", "b"]]);
+
+        let mut undirected = Graph::undirected();
+        undirected.add_edge(1, 2);
+        undirected.add_edge(3, 4);
+        undirected.add_node(5);
+        assert_eq!(undirected.strongly_connected_components().len(), 3);
+    }
+
+    #[test]
+    fn minimum_spanning_tree() {
+        let mut graph = Graph::undirected();
+        graph.add_weighted_edge('a', 'b', 4);
+        graph.add_weighted_edge('a', 'c', 1);
+        graph.add_weighted_edge('b', 'c', 2);
+        graph.add_weighted_edge('b', 'd', 5);
+        graph.add_weighted_edge('c', 'd', 8);
+        graph.add_weighted_edge('x', 'y', 7);
+
+        let (total, tree) = graph.minimum_spanning_tree();
+        assert_eq!(total, 15);
+        assert_eq!(
+            tree,
+            vec![
+                (&'a', &'c', 1),
+                (&'b', &'c', 2),
+                (&'b', &'d', 5),
+                (&'x', &'y', 7)
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "only defined for undirected graphs")]
+    fn minimum_spanning_tree_of_directed_graph_panics() {
+        directed(&[("a", "b")]).minimum_spanning_tree();
+    }
+
+    #[test]
+    fn counting_paths() {
+        let graph = directed(&[
+            ("a", "b"),
+            ("a", "c"),
+            ("b", "d"),
+            ("c", "d"),
+            ("d", "e"),
+            ("c", "e"),
+        ]);
+        assert_eq!(graph.count_paths(&"a", &"e"), Some(3));
+        assert_eq!(graph.count_paths(&"a", &"a"), Some(1));
+        assert_eq!(graph.count_paths(&"e", &"a"), Some(0));
+        assert_eq!(graph.count_paths(&"a", &"missing"), Some(0));
+        assert_eq!(graph.count_paths_through(&"a", &"e", &["d"]), Some(2));
+        assert_eq!(graph.count_paths_through(&"a", &"e", &["d", "c"]), Some(1));
+        assert_eq!(graph.count_paths_through(&"a", &"e", &["b", "c"]), Some(0));
+
+        assert_eq!(
+            directed(&[("a", "b"), ("b", "a")]).count_paths(&"a", &"b"),
+            None
+        );
+        let mut undirected = Graph::undirected();
+        undirected.add_edge("a", "b");
+        assert_eq!(undirected.count_paths(&"a", &"b"), None);
+    }
+
+    #[test]
+    fn counting_too_many_paths() {
+        let graph = diamonds(130);
+        assert_eq!(graph.count_paths(&0, &(3 * 127)), Some(1 << 127));
+        assert_eq!(graph.count_paths(&0, &(3 * 130)), None);
+        // Only the counts along the requested paths matter.
+        assert_eq!(graph.count_paths(&(3 * 100), &(3 * 130)), Some(1 << 30));
+        // Both segments fit into a u128, but their product doesn't.
+        assert_eq!(graph.count_paths_through(&0, &(3 * 128), &[3 * 64]), None);
+        assert_eq!(
+            graph.count_paths_through(&0, &(3 * 127), &[3 * 64]),
+            Some(1 << 127)
+        );
+
+        // No paths lead to an unconnected node, even though there are too many to the waypoint.
+        let mut graph = graph;
+        graph.add_node(1000);
+        assert_eq!(graph.count_paths_through(&0, &1000, &[3 * 130]), Some(0));
+    }
+
+    #[test]
+    fn union_find() {
+        let mut sets = UnionFind::new(5);
+        assert_eq!(sets.set_count(), 5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(sets.union(1, 4));
+        assert!(!sets.union(0, 3));
+        assert!(sets.same_set(0, 4));
+        assert!(!sets.same_set(0, 2));
+        assert_eq!(sets.set_size(3), 4);
+        assert_eq!(sets.set_size(2), 1);
+        assert_eq!(sets.set_count(), 2);
+    }
+}
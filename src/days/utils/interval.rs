@@ -0,0 +1,377 @@
+use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
+
+/// Integer types usable as the bounds of an [`Interval`].
+pub trait Discrete: Copy + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn successor(self) -> Option<Self>;
+    fn predecessor(self) -> Option<Self>;
+
+    /// The number of values from `self` to `other`, excluding `other`. `other` must not be smaller.
+    fn distance(self, other: Self) -> u128;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {$(
+        impl Discrete for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn successor(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn predecessor(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn distance(self, other: Self) -> u128 {
+                (other as i128 - self as i128) as u128
+            }
+        }
+    )*};
+}
+
+impl_discrete!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A non-empty range of integers, with both bounds inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T> Interval<T>
+where
+    T: Discrete,
+{
+    /// Creates an interval from inclusive bounds. Returns None if `end` is smaller than `start`.
+    pub fn new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// Converts any range, like `a..b`, `a..=b` or `a..`, returning None if it is empty.
+    pub fn from_range(range: impl RangeBounds<T>) -> Option<Self> {
+        let start = match range.start_bound() {
+            Bound::Included(&v) => v,
+            Bound::Excluded(&v) => v.successor()?,
+            Bound::Unbounded => T::MIN,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&v) => v,
+            Bound::Excluded(&v) => v.predecessor()?,
+            Bound::Unbounded => T::MAX,
+        };
+
+        Self::new(start, end)
+    }
+
+    /// The number of values within the interval, which is never 0.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u128 {
+        self.start.distance(self.end) + 1
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<T> {
+        let end = self.end;
+        std::iter::successors(Some(self.start), move |v| {
+            if *v < end { v.successor() } else { None }
+        })
+    }
+}
+
+/// A set of integers, stored as sorted, disjoint intervals.
+///
+/// Overlapping intervals, as well as intervals touching at their ends (like `1..=3` and `4..=5`), are merged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Discrete,
+{
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Adds all values of a range, like `a..b`, `a..=b` or `a..`. Empty ranges are ignored.
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        if let Some(interval) = Interval::from_range(range) {
+            self.insert_interval(interval);
+        }
+    }
+
+    pub fn insert_interval(&mut self, interval: Interval<T>) {
+        // the intervals ending before the new one starts, without touching it.
+        let first = self.intervals.partition_point(|v| {
+            v.end
+                .successor()
+                .is_some_and(|after_end| after_end < interval.start)
+        });
+        // the intervals starting before the new one ends, or directly after it.
+        let last = self.intervals.partition_point(|v| {
+            v.start
+                .predecessor()
+                .is_none_or(|before_start| before_start <= interval.end)
+        });
+
+        let merged = if first < last {
+            Interval {
+                start: interval.start.min(self.intervals[first].start),
+                end: interval.end.max(self.intervals[last - 1].end),
+            }
+        } else {
+            interval
+        };
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Whether the value is within any of the intervals, found using a binary search.
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|v| v.end < value);
+        self.intervals.get(index).is_some_and(|v| v.start <= value)
+    }
+
+    /// The number of values within the set.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(|v| v.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The merged intervals, sorted by their start.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    /// Iterates over every value within the set, in ascending order.
+    pub fn values(&self) -> impl Iterator<Item = T> {
+        self.intervals.iter().flat_map(|v| v.iter())
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for interval in &other.intervals {
+            result.insert_interval(*interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+
+        while let (Some(left), Some(right)) = (self.intervals.get(a), other.intervals.get(b)) {
+            if let Some(overlap) =
+                Interval::new(left.start.max(right.start), left.end.min(right.end))
+            {
+                intervals.push(overlap);
+            }
+
+            if left.end < right.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+
+        Self { intervals }
+    }
+
+    /// All values of the type which aren't within the set.
+    pub fn complement(&self) -> Self {
+        let mut intervals = Vec::with_capacity(self.intervals.len() + 1);
+        let mut next_start = Some(T::MIN);
+
+        for interval in &self.intervals {
+            if let Some(start) = next_start
+                && let Some(end) = interval.start.predecessor()
+                && let Some(gap) = Interval::new(start, end)
+            {
+                intervals.push(gap);
+            }
+            next_start = interval.end.successor();
+        }
+        if let Some(start) = next_start {
+            intervals.push(Interval { start, end: T::MAX });
+        }
+
+        Self { intervals }
+    }
+
+    /// The values within this set, but not within `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement())
+    }
+}
+
+impl<T, R> FromIterator<R> for IntervalSet<T>
+where
+    T: Discrete,
+    R: RangeBounds<T>,
+{
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, R> Extend<R> for IntervalSet<T>
+where
+    T: Discrete,
+    R: RangeBounds<T>,
+{
+    fn extend<I: IntoIterator<Item = R>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: i32, end: i32) -> Interval<i32> {
+        Interval::new(start, end).unwrap()
+    }
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(start, end)| start..=end).collect()
+    }
+
+    #[test]
+    fn intervals_from_ranges() {
+        assert_eq!(Interval::from_range(1..4), Some(interval(1, 3)));
+        assert_eq!(Interval::from_range(1..=4), Some(interval(1, 4)));
+        assert_eq!(Interval::from_range(3..3), None);
+        assert_eq!(Interval::new(5, 4), None);
+        assert_eq!(Interval::from_range(..=2_u8), Interval::new(0, 2));
+        assert_eq!(Interval::from_range(250_u8..), Interval::new(250, 255));
+        assert_eq!(Interval::<u8>::from_range(..0), None);
+
+        assert_eq!(interval(-2, 2).len(), 5);
+        assert_eq!(
+            Interval::from_range(..).map(|v: Interval<i64>| v.len()),
+            Some(1 << 64)
+        );
+        assert_eq!(interval(1, 3).iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(Interval::new(254_u8, 255).unwrap().iter().count(), 2);
+        assert!(interval(1, 3).overlaps(&interval(3, 5)));
+        assert!(!interval(1, 3).overlaps(&interval(4, 5)));
+    }
+
+    #[test]
+    fn adjacent_and_overlapping_ranges_merge() {
+        assert_eq!(set(&[(1, 3), (4, 6)]).intervals(), &[interval(1, 6)]);
+        assert_eq!(set(&[(1, 3), (3, 5)]).intervals(), &[interval(1, 5)]);
+        assert_eq!(set(&[(4, 6), (1, 3)]).intervals(), &[interval(1, 6)]);
+        assert_eq!(
+            set(&[(1, 3), (5, 6)]).intervals(),
+            &[interval(1, 3), interval(5, 6)]
+        );
+        // A range bridging several intervals merges them all.
+        assert_eq!(
+            set(&[(1, 2), (5, 6), (9, 10), (3, 8)]).intervals(),
+            &[interval(1, 10)]
+        );
+        assert_eq!(set(&[(1, 10), (3, 4)]).intervals(), &[interval(1, 10)]);
+    }
+
+    #[test]
+    fn single_point_ranges() {
+        let points = set(&[(5, 5), (7, 7)]);
+        assert_eq!(points.len(), 2);
+        assert!(points.contains(5) && points.contains(7));
+        assert!(!points.contains(6));
+
+        assert_eq!(set(&[(5, 5), (6, 6)]).intervals(), &[interval(5, 6)]);
+        assert_eq!(set(&[(5, 5), (5, 5)]).intervals(), &[interval(5, 5)]);
+        assert_eq!(
+            set(&[(1, 9)]).difference(&set(&[(5, 5)])).intervals(),
+            &[interval(1, 4), interval(6, 9)]
+        );
+        assert_eq!(set(&[(1, 9)]).intersection(&points), points);
+    }
+
+    #[test]
+    fn subtracting_everything_leaves_an_empty_set() {
+        let a = set(&[(1, 3), (6, 8)]);
+        let empty = a.difference(&set(&[(0, 10)]));
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert!(!empty.contains(2));
+
+        assert!(a.difference(&a).is_empty());
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+    }
+
+    #[test]
+    fn intersecting_with_an_empty_set() {
+        let a = set(&[(1, 3), (6, 8)]);
+        assert!(a.intersection(&IntervalSet::new()).is_empty());
+        assert!(IntervalSet::new().intersection(&a).is_empty());
+        assert_eq!(
+            a.intersection(&set(&[(3, 6)])).intervals(),
+            &[interval(3, 3), interval(6, 6)]
+        );
+    }
+
+    #[test]
+    fn union_complement_and_values() {
+        let a = set(&[(1, 3), (6, 8)]);
+        let b = set(&[(4, 4), (10, 11)]);
+        assert_eq!(
+            a.union(&b).intervals(),
+            &[interval(1, 4), interval(6, 8), interval(10, 11)]
+        );
+        assert_eq!(a.union(&IntervalSet::new()), a);
+        assert_eq!(a.values().collect::<Vec<_>>(), vec![1, 2, 3, 6, 7, 8]);
+
+        let bytes: IntervalSet<u8> = [0..=3, 250..=255].into_iter().collect();
+        assert_eq!(
+            bytes.complement().intervals(),
+            &[Interval::new(4, 249).unwrap()]
+        );
+        assert!(IntervalSet::<u8>::new().complement().contains(0));
+        assert_eq!(IntervalSet::<u8>::new().complement().len(), 256);
+        assert_eq!(bytes.complement().complement(), bytes);
+    }
+
+    #[test]
+    // Empty ranges have to be ignored.
+    #[allow(clippy::reversed_empty_ranges)]
+    fn extremes_of_the_type() {
+        let mut full = IntervalSet::new();
+        full.insert(i8::MIN..=-1);
+        full.insert(0..=i8::MAX);
+        assert_eq!(
+            full.intervals(),
+            &[Interval::new(i8::MIN, i8::MAX).unwrap()]
+        );
+        assert_eq!(full.len(), 256);
+        assert!(full.complement().is_empty());
+
+        let mut set = IntervalSet::new();
+        set.extend([0_i32..0, 5..3]);
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,818 @@
+//! Exact Gaussian elimination of linear systems, over GF(2) and over the rationals.
+//!
+//! Floating point elimination accumulates rounding errors, which turn integer solutions into
+//! almost-integers and make singular systems look solvable. Both systems here are exact instead:
+//! [`Gf2System`] stores its equations as bitsets, where adding rows is a xor of whole words, and
+//! [`LinearSystem`] uses [`Rational`] numbers, so integer systems stay exact as well.
+//!
+//! Solving a system returns its whole solution space, as a particular solution plus a basis of the
+//! null space, with one basis vector for each free variable. Puzzles usually ask for one specific
+//! solution, so both solution spaces can search the free variables for the minimum-weight solution.
+
+use super::math::{Integer, gcd};
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A fixed number of bits, packed into words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVector {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVector {
+    /// Creates a vector of `len` zero bits.
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Creates a vector of `len` bits, where only the bits at the given indices are set.
+    pub fn from_ones(len: usize, ones: impl IntoIterator<Item = usize>) -> Self {
+        let mut vector = Self::new(len);
+        for index in ones {
+            vector.set(index, true);
+        }
+        vector
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if the index is out of bounds.
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "Bit index out of bounds.");
+        self.words[index / 64] >> (index % 64) & 1 == 1
+    }
+
+    /// Panics if the index is out of bounds.
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "Bit index out of bounds.");
+        match value {
+            true => self.words[index / 64] |= 1 << (index % 64),
+            false => self.words[index / 64] &= !(1 << (index % 64)),
+        }
+    }
+
+    /// Adds another vector of the same length, which is a xor in GF(2).
+    pub fn xor_assign(&mut self, other: &BitVector) {
+        assert_eq!(self.len, other.len, "Bit vectors of different lengths.");
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+
+    /// The number of set bits, which is the weight of a solution.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterates over the indices of all set bits, in ascending order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+/// A system of linear equations over GF(2), where addition is a xor.
+///
+/// For example, which buttons to press an odd number of times, if every button toggles some lights.
+#[derive(Debug, Clone)]
+pub struct Gf2System {
+    variables: usize,
+    // Every equation stores the coefficients of the variables, followed by the right hand side.
+    rows: Vec<BitVector>,
+}
+
+impl Gf2System {
+    pub fn new(variables: usize) -> Self {
+        Self {
+            variables,
+            rows: Vec::new(),
+        }
+    }
+
+    /// The number of variables.
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+
+    /// Adds the equation `x[i] ^ x[j] ^ ... = value`, where `ones` are the indices i, j, ...
+    ///
+    /// Panics if an index is not a variable.
+    pub fn add_equation(&mut self, ones: impl IntoIterator<Item = usize>, value: bool) {
+        let mut row = BitVector::new(self.variables + 1);
+        for index in ones {
+            assert!(index < self.variables, "Not a variable.");
+            row.set(index, !row.get(index));
+        }
+        row.set(self.variables, value);
+        self.rows.push(row);
+    }
+
+    /// Adds the equation `coefficients · x = value`.
+    pub fn add_row(&mut self, coefficients: &BitVector, value: bool) {
+        assert_eq!(
+            coefficients.len(),
+            self.variables,
+            "Not a coefficient per variable."
+        );
+        self.add_equation(coefficients.iter_ones(), value);
+    }
+
+    /// Returns all solutions of the system, or None if it has no solution.
+    pub fn solve(&self) -> Option<Gf2Solution> {
+        let mut rows = self.rows.clone();
+        let mut pivots = Vec::new();
+
+        for column in 0..self.variables {
+            let Some(found) = (pivots.len()..rows.len()).find(|&row| rows[row].get(column)) else {
+                continue;
+            };
+            let row = pivots.len();
+            rows.swap(row, found);
+
+            let pivot = rows[row].clone();
+            for (other, target) in rows.iter_mut().enumerate() {
+                if other != row && target.get(column) {
+                    target.xor_assign(&pivot);
+                }
+            }
+            pivots.push(column);
+        }
+
+        // Remaining rows have no coefficients left, so they read `0 = value`.
+        if rows[pivots.len()..]
+            .iter()
+            .any(|row| row.get(self.variables))
+        {
+            return None;
+        }
+
+        let mut particular = BitVector::new(self.variables);
+        for (row, &column) in pivots.iter().enumerate() {
+            particular.set(column, rows[row].get(self.variables));
+        }
+
+        let free_variables: Vec<usize> = (0..self.variables)
+            .filter(|column| !pivots.contains(column))
+            .collect();
+        let null_space = free_variables
+            .iter()
+            .map(|&free| {
+                let mut basis = BitVector::new(self.variables);
+                basis.set(free, true);
+                for (row, &column) in pivots.iter().enumerate() {
+                    basis.set(column, rows[row].get(free));
+                }
+                basis
+            })
+            .collect();
+
+        Some(Gf2Solution {
+            particular,
+            null_space,
+            free_variables,
+        })
+    }
+}
+
+/// All solutions of a [`Gf2System`]: the particular solution plus any sum of null space vectors.
+#[derive(Debug, Clone)]
+pub struct Gf2Solution {
+    particular: BitVector,
+    null_space: Vec<BitVector>,
+    free_variables: Vec<usize>,
+}
+
+impl Gf2Solution {
+    /// The solution where all free variables are false.
+    pub fn particular(&self) -> &BitVector {
+        &self.particular
+    }
+
+    /// A basis of the null space, with one vector for each free variable.
+    pub fn null_space(&self) -> &[BitVector] {
+        &self.null_space
+    }
+
+    /// The variables which may be chosen freely, in the same order as the [`null_space`](Self::null_space).
+    pub fn free_variables(&self) -> &[usize] {
+        &self.free_variables
+    }
+
+    /// Whether there is only a single solution.
+    pub fn is_unique(&self) -> bool {
+        self.null_space.is_empty()
+    }
+
+    /// The number of solutions, or None if it doesn't fit into an u128.
+    pub fn count(&self) -> Option<u128> {
+        1_u128.checked_shl(self.null_space.len() as u32)
+    }
+
+    /// Iterates over all solutions, which are 2 to the power of the number of free variables.
+    ///
+    /// Consecutive solutions differ by a single null space vector, so each one only costs a xor.
+    pub fn solutions(&self) -> impl Iterator<Item = BitVector> + '_ {
+        assert!(self.null_space.len() < 64, "Too many solutions to iterate.");
+
+        let mut current = self.particular.clone();
+        (0..1_u64 << self.null_space.len()).map(move |i| {
+            // Gray code: the i-th step flips the lowest set bit of i.
+            if i != 0 {
+                current.xor_assign(&self.null_space[i.trailing_zeros() as usize]);
+            }
+            current.clone()
+        })
+    }
+
+    /// The solution with the fewest variables set, like the fewest button presses.
+    ///
+    /// Tries every solution, so it takes time exponential in the number of free variables.
+    pub fn min_weight(&self) -> BitVector {
+        self.solutions().min_by_key(|v| v.count_ones()).unwrap()
+    }
+}
+
+/// An exact fraction, always reduced and with a positive denominator.
+///
+/// Arithmetic panics instead of wrapping if a numerator or denominator doesn't fit into an i128.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const ONE: Self = Self {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Creates the reduced fraction `numerator / denominator`. Panics if the denominator is 0.
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "Division by zero.");
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+
+        Self {
+            numerator: overflow_checked(numerator.checked_div(divisor)),
+            denominator: overflow_checked(denominator.checked_div(divisor)),
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// Returns the value as an integer, or None if it is a proper fraction.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    /// Returns `1 / self`. Panics if the value is 0.
+    pub fn recip(self) -> Self {
+        Self::new(self.denominator, self.numerator)
+    }
+}
+
+/// Unwraps the result of a checked operation on a numerator or denominator.
+fn overflow_checked(value: Option<i128>) -> i128 {
+    value.expect("Rational number overflowed an i128.")
+}
+
+impl<T: Integer> From<T> for Rational {
+    fn from(value: T) -> Self {
+        Self {
+            numerator: value.to_i128(),
+            denominator: 1,
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Both denominators are positive, so multiplying by them keeps the order.
+        let left = overflow_checked(self.numerator.checked_mul(other.denominator));
+        let right = overflow_checked(other.numerator.checked_mul(self.denominator));
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // Expanding to the least common denominator keeps the intermediate values small.
+        let divisor = gcd(self.denominator, rhs.denominator);
+        let (left, right) = (self.denominator / divisor, rhs.denominator / divisor);
+
+        let numerator = overflow_checked(
+            self.numerator
+                .checked_mul(right)
+                .zip(rhs.numerator.checked_mul(left))
+                .and_then(|(a, b)| a.checked_add(b)),
+        );
+        Self::new(
+            numerator,
+            overflow_checked(self.denominator.checked_mul(right)),
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // Both fractions are reduced, so only the crosswise pairs can share factors.
+        let first = gcd(self.numerator, rhs.denominator);
+        let second = gcd(rhs.numerator, self.denominator);
+
+        Self::new(
+            overflow_checked((self.numerator / first).checked_mul(rhs.numerator / second)),
+            overflow_checked((self.denominator / second).checked_mul(rhs.denominator / first)),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "Division by zero.");
+        self * rhs.recip()
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            numerator: overflow_checked(self.numerator.checked_neg()),
+            denominator: self.denominator,
+        }
+    }
+}
+
+/// A system of linear equations over the rationals, solved without any rounding.
+///
+/// For example, how often to press every button, if every button increments some counters.
+#[derive(Debug, Clone)]
+pub struct LinearSystem {
+    variables: usize,
+    // Every equation stores the coefficients of the variables, followed by the right hand side.
+    rows: Vec<Vec<Rational>>,
+}
+
+impl LinearSystem {
+    pub fn new(variables: usize) -> Self {
+        Self {
+            variables,
+            rows: Vec::new(),
+        }
+    }
+
+    /// The number of variables.
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+
+    /// Adds the equation `coefficients · x = value`.
+    ///
+    /// Panics if there isn't exactly one coefficient per variable.
+    pub fn add_equation<T>(&mut self, coefficients: &[T], value: T)
+    where
+        T: Into<Rational> + Copy,
+    {
+        assert_eq!(
+            coefficients.len(),
+            self.variables,
+            "Not a coefficient per variable."
+        );
+        self.rows.push(
+            coefficients
+                .iter()
+                .chain([&value])
+                .map(|&v| v.into())
+                .collect(),
+        );
+    }
+
+    /// Returns all solutions of the system, or None if it has no solution.
+    pub fn solve(&self) -> Option<Solution> {
+        let mut rows = self.rows.clone();
+        let mut pivots = Vec::new();
+
+        for column in 0..self.variables {
+            let Some(found) = (pivots.len()..rows.len()).find(|&row| !rows[row][column].is_zero())
+            else {
+                continue;
+            };
+            let row = pivots.len();
+            rows.swap(row, found);
+
+            let scale = rows[row][column].recip();
+            rows[row].iter_mut().for_each(|v| *v = *v * scale);
+
+            let pivot = rows[row].clone();
+            for (other, target) in rows.iter_mut().enumerate() {
+                let factor = target[column];
+                if other == row || factor.is_zero() {
+                    continue;
+                }
+                for (v, p) in target.iter_mut().zip(&pivot) {
+                    *v = *v - factor * *p;
+                }
+            }
+            pivots.push(column);
+        }
+
+        // Remaining rows have no coefficients left, so they read `0 = value`.
+        if rows[pivots.len()..]
+            .iter()
+            .any(|row| !row[self.variables].is_zero())
+        {
+            return None;
+        }
+
+        let mut particular = vec![Rational::ZERO; self.variables];
+        for (row, &column) in pivots.iter().enumerate() {
+            particular[column] = rows[row][self.variables];
+        }
+
+        let free_variables: Vec<usize> = (0..self.variables)
+            .filter(|column| !pivots.contains(column))
+            .collect();
+        let null_space = free_variables
+            .iter()
+            .map(|&free| {
+                let mut basis = vec![Rational::ZERO; self.variables];
+                basis[free] = Rational::ONE;
+                for (row, &column) in pivots.iter().enumerate() {
+                    basis[column] = -rows[row][free];
+                }
+                basis
+            })
+            .collect();
+
+        Some(Solution {
+            particular,
+            null_space,
+            free_variables,
+        })
+    }
+}
+
+/// All solutions of a [`LinearSystem`]: the particular solution plus any combination of null space vectors.
+#[derive(Debug, Clone)]
+pub struct Solution {
+    particular: Vec<Rational>,
+    null_space: Vec<Vec<Rational>>,
+    free_variables: Vec<usize>,
+}
+
+impl Solution {
+    /// The solution where all free variables are 0.
+    pub fn particular(&self) -> &[Rational] {
+        &self.particular
+    }
+
+    /// A basis of the null space, with one vector for each free variable.
+    pub fn null_space(&self) -> &[Vec<Rational>] {
+        &self.null_space
+    }
+
+    /// The variables which may be chosen freely, in the same order as the [`null_space`](Self::null_space).
+    pub fn free_variables(&self) -> &[usize] {
+        &self.free_variables
+    }
+
+    /// Whether there is only a single solution.
+    pub fn is_unique(&self) -> bool {
+        self.null_space.is_empty()
+    }
+
+    /// The solution with the given values of the free variables.
+    ///
+    /// Panics if there isn't exactly one value per free variable.
+    pub fn evaluate(&self, free_values: &[Rational]) -> Vec<Rational> {
+        assert_eq!(
+            free_values.len(),
+            self.null_space.len(),
+            "Not a value per free variable."
+        );
+
+        let mut solution = self.particular.clone();
+        for (basis, &value) in self.null_space.iter().zip(free_values) {
+            for (v, b) in solution.iter_mut().zip(basis) {
+                *v = *v + *b * value;
+            }
+        }
+        solution
+    }
+
+    /// The solution of non-negative integers with the smallest sum, like the fewest button presses,
+    /// where every variable `i` is at most `upper_bounds[i]`.
+    ///
+    /// Tries every combination of values of the free variables within their bounds, so it takes time
+    /// exponential in the number of free variables. Returns None if there is no such solution.
+    pub fn min_weight_integer(&self, upper_bounds: &[i128]) -> Option<Vec<i128>> {
+        assert_eq!(
+            upper_bounds.len(),
+            self.particular.len(),
+            "Not a bound per variable."
+        );
+
+        let mut best: Option<(i128, Vec<i128>)> = None;
+        self.search_free_variables(0, self.particular.clone(), upper_bounds, &mut best);
+
+        best.map(|(_, solution)| solution)
+    }
+
+    /// Tries all values of the free variables from `free` on, where `current` already contains the
+    /// values of the previous ones.
+    fn search_free_variables(
+        &self,
+        free: usize,
+        current: Vec<Rational>,
+        upper_bounds: &[i128],
+        best: &mut Option<(i128, Vec<i128>)>,
+    ) {
+        if free == self.null_space.len() {
+            let Some(solution) = current
+                .iter()
+                .zip(upper_bounds)
+                .map(|(v, &bound)| v.to_integer().filter(|v| (0..=bound).contains(v)))
+                .collect::<Option<Vec<i128>>>()
+            else {
+                return;
+            };
+
+            let weight = solution.iter().sum();
+            if best.as_ref().is_none_or(|(best, _)| weight < *best) {
+                *best = Some((weight, solution));
+            }
+            return;
+        }
+
+        let basis = &self.null_space[free];
+        let mut current = current;
+        for value in 0..=upper_bounds[self.free_variables[free]] {
+            if value != 0 {
+                for (v, b) in current.iter_mut().zip(basis) {
+                    *v = *v + *b;
+                }
+            }
+            self.search_free_variables(free + 1, current.clone(), upper_bounds, best);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator)
+    }
+
+    #[test]
+    fn rationals_are_reduced() {
+        assert_eq!(r(4, -6), r(-2, 3));
+        assert_eq!(r(-2, 3).denominator(), 3);
+        assert_eq!(r(0, -5), Rational::ZERO);
+        assert_eq!(r(1, 6) + r(1, 3), r(1, 2));
+        assert_eq!(r(1, 6) - r(1, 6), Rational::ZERO);
+        assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+        assert_eq!(r(2, 3) / r(4, 9), r(3, 2));
+        assert_eq!(r(-3, 4).recip(), r(-4, 3));
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert_eq!(r(7, 1).to_integer(), Some(7));
+        assert_eq!(r(7, 2).to_integer(), None);
+        assert_eq!(r(-7, 2).to_string(), "-7/2");
+        assert_eq!(Rational::from(5_u8).to_string(), "5");
+    }
+
+    #[test]
+    fn large_rationals_cancel_before_multiplying() {
+        let big = i128::MAX / 3;
+        // The product of the denominators doesn't fit, but they share the factor big.
+        assert_eq!(r(1, big) + r(1, big), r(2, big));
+        assert_eq!(r(big, 7) * r(7, big), Rational::ONE);
+        assert_eq!(r(big, 2) / r(big, 2), Rational::ONE);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rational number overflowed an i128.")]
+    fn overflowing_rationals_panic() {
+        let _ = Rational::from(i128::MAX) + Rational::ONE;
+    }
+
+    #[test]
+    #[should_panic(expected = "Rational number overflowed an i128.")]
+    fn overflowing_denominators_panic() {
+        let _ = r(1, i128::MAX) * r(1, 2);
+    }
+
+    #[test]
+    fn bit_vectors() {
+        let mut vector = BitVector::from_ones(130, [0, 64, 129]);
+        assert_eq!(vector.count_ones(), 3);
+        assert_eq!(vector.iter_ones().collect::<Vec<_>>(), vec![0, 64, 129]);
+
+        vector.xor_assign(&BitVector::from_ones(130, [0, 1]));
+        assert_eq!(vector.iter_ones().collect::<Vec<_>>(), vec![1, 64, 129]);
+        vector.set(129, false);
+        assert!(!vector.get(129));
+    }
+
+    #[test]
+    fn unique_gf2_solution() {
+        let mut system = Gf2System::new(3);
+        system.add_equation([0, 1], true);
+        system.add_equation([1, 2], false);
+        system.add_equation([0, 1, 2], false);
+
+        let solution = system.solve().unwrap();
+        assert!(solution.is_unique());
+        assert_eq!(solution.count(), Some(1));
+        assert_eq!(
+            solution.particular().iter_ones().collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn inconsistent_gf2_system() {
+        let mut system = Gf2System::new(2);
+        system.add_equation([0, 1], true);
+        system.add_equation([0, 1], false);
+        assert!(system.solve().is_none());
+    }
+
+    #[test]
+    fn gf2_free_variables_and_min_weight() {
+        // Two lights, three buttons: button 2 toggles both lights, buttons 0 and 1 one each.
+        let mut system = Gf2System::new(3);
+        system.add_row(&BitVector::from_ones(3, [0, 2]), true);
+        system.add_row(&BitVector::from_ones(3, [1, 2]), true);
+
+        let solution = system.solve().unwrap();
+        assert_eq!(solution.free_variables(), &[2]);
+        assert_eq!(solution.count(), Some(2));
+
+        let solutions: Vec<Vec<usize>> = solution
+            .solutions()
+            .map(|v| v.iter_ones().collect())
+            .collect();
+        assert_eq!(solutions, vec![vec![0, 1], vec![2]]);
+        assert_eq!(
+            solution.min_weight().iter_ones().collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn unique_rational_solution() {
+        let mut system = LinearSystem::new(2);
+        system.add_equation(&[2, 1], 3);
+        system.add_equation(&[1, 3], 4);
+
+        let solution = system.solve().unwrap();
+        assert!(solution.is_unique());
+        assert_eq!(solution.particular(), &[r(1, 1), r(1, 1)]);
+        assert_eq!(solution.min_weight_integer(&[5, 5]), Some(vec![1, 1]));
+    }
+
+    #[test]
+    fn fractional_solution_has_no_integer_solution() {
+        let mut system = LinearSystem::new(1);
+        system.add_equation(&[2], 1);
+
+        let solution = system.solve().unwrap();
+        assert_eq!(solution.particular(), &[r(1, 2)]);
+        assert_eq!(solution.min_weight_integer(&[10]), None);
+    }
+
+    #[test]
+    fn singular_systems() {
+        // The second equation is twice the first one, so one variable stays free.
+        let mut system = LinearSystem::new(2);
+        system.add_equation(&[1, 1], 2);
+        system.add_equation(&[2, 2], 4);
+        let solution = system.solve().unwrap();
+        assert_eq!(solution.free_variables(), &[1]);
+
+        // With a contradicting right hand side, there is no solution at all.
+        let mut system = LinearSystem::new(2);
+        system.add_equation(&[1, 1], 2);
+        system.add_equation(&[2, 2], 5);
+        assert!(system.solve().is_none());
+    }
+
+    #[test]
+    fn inconsistent_rational_system() {
+        let mut system = LinearSystem::new(3);
+        system.add_equation(&[1, 0, 0], 1);
+        system.add_equation(&[0, 1, 0], 2);
+        system.add_equation(&[1, 1, 0], 4);
+        assert!(system.solve().is_none());
+    }
+
+    #[test]
+    fn rational_free_variables() {
+        let mut system = LinearSystem::new(3);
+        system.add_equation(&[1, 0, 1], 3);
+        system.add_equation(&[0, 1, 1], 5);
+
+        let solution = system.solve().unwrap();
+        assert!(!solution.is_unique());
+        assert_eq!(solution.free_variables(), &[2]);
+        assert_eq!(solution.particular(), &[r(3, 1), r(5, 1), Rational::ZERO]);
+        assert_eq!(
+            solution.null_space(),
+            &[vec![r(-1, 1), r(-1, 1), Rational::ONE]]
+        );
+        assert_eq!(
+            solution.evaluate(&[r(2, 1)]),
+            vec![r(1, 1), r(3, 1), r(2, 1)]
+        );
+    }
+
+    #[test]
+    fn min_weight_integer_searches_the_free_variables() {
+        // Counters 0 and 1 reach 3 and 5; buttons 0 and 1 increment one each, button 2 both.
+        let mut system = LinearSystem::new(3);
+        system.add_equation(&[1, 0, 1], 3);
+        system.add_equation(&[0, 1, 1], 5);
+        let solution = system.solve().unwrap();
+
+        // Pressing button 2 three times saves three presses.
+        assert_eq!(solution.min_weight_integer(&[5, 5, 5]), Some(vec![0, 2, 3]));
+        // Its bound only allows pressing it once.
+        assert_eq!(solution.min_weight_integer(&[5, 5, 1]), Some(vec![2, 4, 1]));
+        // Button 1 can't be pressed enough times.
+        assert_eq!(solution.min_weight_integer(&[5, 1, 5]), None);
+    }
+}
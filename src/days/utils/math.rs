@@ -0,0 +1,373 @@
+//! Number theory and digit helpers, generic over the primitive integer types.
+//!
+//! Intermediate results are computed using 128-bit integers, so they don't overflow for
+//! the smaller types. [`mod_inverse`] and [`crt`] need their moduli to fit into an `i128`.
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn is_negative(self) -> bool;
+    fn unsigned_abs_u128(self) -> u128;
+    /// Converts the value, which has to fit into an `i128`.
+    fn to_i128(self) -> i128;
+    /// Converts a value, which has to fit into the type.
+    fn from_u128(value: u128) -> Self;
+    /// Converts a value, which has to fit into the type.
+    fn from_i128(value: i128) -> Self;
+    fn rem_euclid(self, modulus: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*; is_negative: $is_negative:expr) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn is_negative(self) -> bool {
+                $is_negative(self)
+            }
+
+            fn unsigned_abs_u128(self) -> u128 {
+                match self.is_negative() {
+                    true => (self as i128).unsigned_abs(),
+                    false => self as u128,
+                }
+            }
+
+            fn to_i128(self) -> i128 {
+                self.try_into().expect("value doesn't fit into an i128")
+            }
+
+            fn from_u128(value: u128) -> Self {
+                value.try_into().expect("value doesn't fit into the integer type")
+            }
+
+            fn from_i128(value: i128) -> Self {
+                value.try_into().expect("value doesn't fit into the integer type")
+            }
+
+            fn rem_euclid(self, modulus: Self) -> Self {
+                <$t>::rem_euclid(self, modulus)
+            }
+        }
+    )*};
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize; is_negative: |_| false);
+impl_integer!(i8, i16, i32, i64, i128, isize; is_negative: |v| v < 0);
+
+/// Iterates over the decimal digits of a number, without allocating.
+///
+/// Negative numbers yield the digits of their absolute value.
+#[derive(Debug, Clone)]
+pub struct Digits {
+    value: u128,
+    divisor: u128,
+    remaining: u32,
+}
+
+impl Iterator for Digits {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let digit = self.value / self.divisor % 10;
+        self.divisor /= 10;
+        self.remaining -= 1;
+        Some(digit as u8)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+impl DoubleEndedIterator for Digits {
+    fn next_back(&mut self) -> Option<u8> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let digit = self.value % 10;
+        self.value /= 10;
+        self.divisor /= 10;
+        self.remaining -= 1;
+        Some(digit as u8)
+    }
+}
+
+impl ExactSizeIterator for Digits {}
+
+/// The decimal digits of a number, starting with the most significant one. `0` has a single digit.
+pub fn digits<T: Integer>(number: T) -> Digits {
+    let remaining = digit_count(number);
+    Digits {
+        value: number.unsigned_abs_u128(),
+        divisor: 10_u128.pow(remaining - 1),
+        remaining,
+    }
+}
+
+/// The number of decimal digits of a number, ignoring the sign. `0` has a single digit.
+pub fn digit_count<T: Integer>(number: T) -> u32 {
+    number.unsigned_abs_u128().checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Appends the digits of `b` to `a`, like `concat_numbers(12, 345) == 12345`. `b` must not be negative.
+pub fn concat_numbers<T: Integer>(a: T, b: T) -> T {
+    a * T::from_u128(10_u128.pow(digit_count(b))) + b
+}
+
+/// Splits a number into the part before and the `low_digits` digits after,
+/// like `split_number(12345, 2) == (123, 45)`.
+pub fn split_number<T: Integer>(number: T, low_digits: u32) -> (T, T) {
+    let divisor = T::from_u128(10_u128.pow(low_digits));
+    (number / divisor, number % divisor)
+}
+
+/// The remainder of a division, which is never negative (for a positive modulus).
+pub fn mod_euclid<T: Integer>(number: T, modulus: T) -> T {
+    number.rem_euclid(modulus)
+}
+
+/// The greatest common divisor. It is never negative, and `gcd(0, 0) == 0`.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.unsigned_abs_u128(), b.unsigned_abs_u128());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    T::from_u128(a)
+}
+
+/// The least common multiple. It is never negative, and `0` if either number is `0`.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    let (a, b) = (a.unsigned_abs_u128(), b.unsigned_abs_u128());
+    T::from_u128(a / gcd(a, b) * b)
+}
+
+/// Adds two numbers smaller than `modulus`, without overflowing.
+fn add_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+/// Multiplies two numbers modulo `modulus`, without overflowing.
+fn mul_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+
+    // double-and-add, for moduli beyond 64 bits.
+    let (mut a, mut b, mut result) = (a % modulus, b % modulus, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b >>= 1;
+    }
+    result
+}
+
+/// Calculates `base^exponent mod modulus` using exponentiation by squaring.
+///
+/// Negative bases are reduced first, so the result is never negative. The modulus must be positive.
+pub fn mod_pow<T: Integer>(base: T, mut exponent: u64, modulus: T) -> T {
+    let modulus_u = modulus.unsigned_abs_u128();
+    let mut base = base.rem_euclid(modulus).unsigned_abs_u128();
+    let mut result = 1 % modulus_u;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus_u);
+        }
+        base = mul_mod(base, base, modulus_u);
+        exponent >>= 1;
+    }
+
+    T::from_u128(result)
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    (old_r, old_x, old_y)
+}
+
+/// The number `x` with `number * x ≡ 1 (mod modulus)`, or None if they aren't coprime.
+pub fn mod_inverse<T: Integer>(number: T, modulus: T) -> Option<T> {
+    let modulus_i = modulus.to_i128();
+    let number_i = number.to_i128().rem_euclid(modulus_i);
+
+    let (g, x, _) = extended_gcd(number_i, modulus_i);
+    (g == 1).then(|| T::from_i128(x.rem_euclid(modulus_i)))
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` using the Chinese remainder theorem.
+///
+/// The moduli don't need to be coprime, but have to be positive. Returns the smallest non-negative solution
+/// and the least common multiple of the moduli, or None if the congruences contradict each other.
+/// Panics if the least common multiple doesn't fit into an `i128`.
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut result: i128 = 0;
+    let mut combined_modulus: i128 = 1;
+
+    for &(residue, modulus) in congruences {
+        let modulus = modulus.to_i128();
+        let residue = residue.to_i128().rem_euclid(modulus);
+
+        let (g, x, _) = extended_gcd(combined_modulus, modulus);
+        if (residue - result) % g != 0 {
+            return None;
+        }
+
+        // result + combined_modulus * k ≡ residue (mod modulus)
+        let step = modulus / g;
+        let k = mul_mod(
+            ((residue - result) / g).rem_euclid(step) as u128,
+            x.rem_euclid(step) as u128,
+            step as u128,
+        ) as i128;
+        let overflow = "the least common multiple of the moduli doesn't fit into an i128";
+        // k < step, so the new result is smaller than the new combined modulus.
+        result = combined_modulus
+            .checked_mul(k)
+            .and_then(|v| v.checked_add(result))
+            .expect(overflow);
+        combined_modulus = combined_modulus.checked_mul(step).expect(overflow);
+    }
+
+    Some((T::from_i128(result), T::from_i128(combined_modulus)))
+}
+
+/// The largest number whose square is not larger than `number`. Panics for negative numbers.
+pub fn isqrt<T: Integer>(number: T) -> T {
+    assert!(!number.is_negative(), "Square root of a negative number.");
+    T::from_u128(number.unsigned_abs_u128().isqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_in_both_directions() {
+        assert_eq!(digits(1203_u32).collect::<Vec<_>>(), vec![1, 2, 0, 3]);
+        assert_eq!(digits(1203_u32).rev().collect::<Vec<_>>(), vec![3, 0, 2, 1]);
+        assert_eq!(digits(0_u8).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(digits(-45_i64).collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(digits(u128::MAX).len(), 39);
+
+        let mut both = digits(12345_u64);
+        assert_eq!((both.next(), both.next_back()), (Some(1), Some(5)));
+        assert_eq!(both.len(), 3);
+        assert_eq!(both.collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn digit_arithmetic() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(9), 1);
+        assert_eq!(digit_count(10), 2);
+        assert_eq!(digit_count(-100_i32), 3);
+        assert_eq!(digit_count(u64::MAX), 20);
+
+        assert_eq!(concat_numbers(12, 345), 12345);
+        assert_eq!(concat_numbers(7_u64, 0), 70);
+        assert_eq!(split_number(12345, 2), (123, 45));
+        assert_eq!(split_number(12345, 0), (12345, 0));
+    }
+
+    #[test]
+    fn divisors_and_multiples() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6_i32), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(mod_euclid(-7, 3), 2);
+        assert_eq!(mod_euclid(7, 3), 1);
+        assert_eq!(isqrt(99_u32), 9);
+        assert_eq!(isqrt(100_i64), 10);
+        assert_eq!(isqrt(0), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Square root of a negative number.")]
+    fn isqrt_of_negative_panics() {
+        isqrt(-1);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        // A modulus beyond 64 bits, where products don't fit into a u128.
+        assert_eq!(
+            mod_pow(3_u128, 10_u64.pow(18), (1 << 100) + 277),
+            1104468700781802223323696933023
+        );
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn chinese_remainder_theorem() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(-1, 3), (-1, 5)]), Some((14, 15)));
+        // Moduli which aren't coprime.
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+
+        // The combined modulus only just fits into an i128.
+        let (m1, m2) = ((1_u128 << 63) - 25, (1_u128 << 64) - 59);
+        let (x, modulus) = crt(&[(123456789123456789, m1), (987654321987654321, m2)]).unwrap();
+        assert_eq!(modulus, m1 * m2);
+        assert_eq!(x, 1771292301986339027912274084697227357);
+    }
+
+    #[test]
+    #[should_panic(expected = "the least common multiple of the moduli doesn't fit into an i128")]
+    fn crt_with_too_large_moduli_panics() {
+        crt(&[(1_u128, (1 << 64) - 59), (2, (1 << 64) - 83)]);
+    }
+}
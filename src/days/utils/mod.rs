@@ -0,0 +1,616 @@
+pub mod automaton;
+mod bitgrid;
+pub mod graph;
+mod interval;
+pub mod linalg;
+pub mod math;
+pub mod parse;
+pub mod pathfinding;
+mod point;
+pub mod search;
+mod sparse;
+mod transform;
+
+pub use bitgrid::{BitGrid, NeighbourCounts};
+pub use interval::{Discrete, Interval, IntervalSet};
+pub use point::{Point2, Point3};
+pub use sparse::SparseGrid;
+pub use transform::MapView;
+
+/// A general purpose struct able to store 2-Dimensional maps of Tiles.
+///
+/// Can be parsed from a multi-line &str, if the T type implements [`From<char>`](std::convert::From).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Map<T> {
+    tiles: Vec<Vec<T>>,
+    dim: (usize, usize),
+}
+
+impl<T> Map<T> {
+    /// Returns the dimensions of the map as a tuple `(x: usize, y: usize)`
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    /// Gets the tile of a specified position.
+    ///
+    /// May return None, if the position is outside the dimensions.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.tiles.get(y)?.get(x)
+    }
+
+    /// Sets the tile of a specified position.
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.tiles[y][x] = value;
+    }
+
+    /// Get the internally stored tile data.
+    pub fn get_raw_tiles(&self) -> &Vec<Vec<T>> {
+        &self.tiles
+    }
+
+    /// Get the internally stored tile data as mutable reference.
+    pub fn get_raw_tiles_mut(&mut self) -> &mut Vec<Vec<T>> {
+        &mut self.tiles
+    }
+
+    /// Gets the tile at a point. May return None, if the point is outside the dimensions.
+    pub fn get_point(&self, point: Point2<usize>) -> Option<&T> {
+        self.get(point.x, point.y)
+    }
+
+    /// Gets the tile at a signed point. Returns None, if the point is negative or outside the dimensions.
+    pub fn get_point_signed(&self, point: Point2<isize>) -> Option<&T> {
+        self.get_signed(point.x, point.y)
+    }
+
+    /// Gets the tile at a point as mutable reference.
+    pub fn get_point_mut(&mut self, point: Point2<usize>) -> Option<&mut T> {
+        self.tiles.get_mut(point.y)?.get_mut(point.x)
+    }
+
+    /// Sets the tile at a point.
+    pub fn set_point(&mut self, point: Point2<usize>, value: T) {
+        self.set(point.x, point.y, value)
+    }
+
+    /// Whether the point is within the dimensions of the map.
+    pub fn contains_point(&self, point: Point2<usize>) -> bool {
+        self.contains(point.x, point.y)
+    }
+
+    /// Iterates over all tiles of the map together with their point, line by line.
+    pub fn iter_points(&self) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        self.iter().map(|(position, v)| (position.into(), v))
+    }
+
+    /// Iterates over all points of the map, line by line.
+    pub fn points(&self) -> impl Iterator<Item = Point2<usize>> + use<T> {
+        self.positions().map(Point2::from)
+    }
+
+    /// Finds the point of the first tile matching the predicate, line by line.
+    pub fn find_point(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point2<usize>> {
+        self.find(predicate).map(Point2::from)
+    }
+
+    /// Iterates over the points of all tiles matching the predicate, line by line.
+    pub fn find_all_points<'a>(
+        &'a self,
+        predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Point2<usize>> + 'a {
+        self.find_all(predicate).map(Point2::from)
+    }
+
+    /// Iterates over the up to 4 orthogonally adjacent tiles and their points.
+    pub fn neighbours4_point(
+        &self,
+        point: Point2<usize>,
+    ) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        self.neighbours4(point.x, point.y)
+            .map(|(position, v)| (position.into(), v))
+    }
+
+    /// Iterates over the up to 8 orthogonally and diagonally adjacent tiles and their points.
+    pub fn neighbours8_point(
+        &self,
+        point: Point2<usize>,
+    ) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        self.neighbours8(point.x, point.y)
+            .map(|(position, v)| (position.into(), v))
+    }
+
+    /// Creates a Map of the specified dimensions, with every tile set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            tiles: vec![vec![value; width]; height],
+            dim: (width, height),
+        }
+    }
+
+    /// Creates a Map from a multi-line &str, converting every char to a tile using `f`.
+    pub fn from_str_with(value: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let tiles: Vec<Vec<T>> = value
+            .lines()
+            .map(|line| line.chars().map(&mut f).collect())
+            .collect();
+
+        Self {
+            dim: (tiles.first().map(|v| v.len()).unwrap_or(0), tiles.len()),
+            tiles,
+        }
+    }
+
+    /// Gets the tile of a specified signed position.
+    ///
+    /// Returns None, if the position is negative or outside the dimensions.
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<&T> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.get(x as usize, y as usize)
+    }
+
+    /// Whether the position is within the dimensions of the map.
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.dim.0 && y < self.dim.1
+    }
+
+    /// Iterates over all positions of the map, line by line.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let (width, height) = self.dim;
+        (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Iterates over all tiles of the map together with their position, line by line.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .flat_map(|(y, line)| line.iter().enumerate().map(move |(x, v)| ((x, y), v)))
+    }
+
+    /// Finds the position of the first tile matching the predicate, line by line.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, v)| predicate(v))
+            .map(|(position, _)| position)
+    }
+
+    /// Iterates over the positions of all tiles matching the predicate, line by line.
+    pub fn find_all<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.iter()
+            .filter(move |(_, v)| predicate(v))
+            .map(|(position, _)| position)
+    }
+
+    /// Iterates over the neighbours at the specified offsets, skipping those outside the map.
+    fn neighbours_at<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = ((usize, usize), &'a T)> {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let (nx, ny) = (x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?);
+            Some(((nx, ny), self.get(nx, ny)?))
+        })
+    }
+
+    /// Iterates over the up to 4 orthogonally adjacent tiles and their positions.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        const OFFSETS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+        self.neighbours_at(x, y, &OFFSETS)
+    }
+
+    /// Iterates over the up to 8 orthogonally and diagonally adjacent tiles and their positions.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+        ];
+        self.neighbours_at(x, y, &OFFSETS)
+    }
+}
+
+impl<T> std::ops::Index<Point2<usize>> for Map<T> {
+    type Output = T;
+
+    fn index(&self, index: Point2<usize>) -> &T {
+        &self.tiles[index.y][index.x]
+    }
+}
+
+impl<T> std::ops::IndexMut<Point2<usize>> for Map<T> {
+    fn index_mut(&mut self, index: Point2<usize>) -> &mut T {
+        &mut self.tiles[index.y][index.x]
+    }
+}
+
+impl<T> std::fmt::Display for Map<T>
+where
+    T: std::fmt::Display,
+{
+    /// Renders the map line by line, without a trailing line break.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, line) in self.tiles.iter().enumerate() {
+            if y != 0 {
+                writeln!(f)?;
+            }
+            for tile in line {
+                write!(f, "{}", tile)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> From<Vec<Vec<T>>> for Map<T> {
+    /// Creates a Map from a `Vec<Vec<T>>`.
+    /// The dimensions are derived from the length of the y-Axis and first x line.
+    fn from(value: Vec<Vec<T>>) -> Self {
+        Self {
+            dim: (value[0].len(), value.len()),
+            tiles: value,
+        }
+    }
+}
+
+impl<T> From<&str> for Map<T>
+where
+    T: From<char>,
+{
+    fn from(value: &str) -> Self {
+        Self::from_str_with(value, T::from)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+    NorthEast,
+    SouthEast,
+    SouthWest,
+    NorthWest,
+}
+
+impl Direction {
+    /// The four cardinal directions, clockwise starting with North.
+    pub const ALL: [Direction; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    /// The four diagonal directions, clockwise starting with NorthEast.
+    pub const DIAGONAL: [Direction; 4] = [
+        Self::NorthEast,
+        Self::SouthEast,
+        Self::SouthWest,
+        Self::NorthWest,
+    ];
+
+    /// All eight cardinal and diagonal directions, clockwise starting with North.
+    pub const ALL_WITH_DIAGONALS: [Direction; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    /// The index of the direction within [`Self::ALL_WITH_DIAGONALS`], in steps of 45° clockwise from North.
+    fn index(&self) -> usize {
+        match self {
+            Self::North => 0,
+            Self::NorthEast => 1,
+            Self::East => 2,
+            Self::SouthEast => 3,
+            Self::South => 4,
+            Self::SouthWest => 5,
+            Self::West => 6,
+            Self::NorthWest => 7,
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// The change of the position when moving into this direction, with `y` growing downwards.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Self::North => (0, -1),
+            Self::NorthEast => (1, -1),
+            Self::East => (1, 0),
+            Self::SouthEast => (1, 1),
+            Self::South => (0, 1),
+            Self::SouthWest => (-1, 1),
+            Self::West => (-1, 0),
+            Self::NorthWest => (-1, -1),
+        }
+    }
+
+    /// Rotates the direction clockwise in steps of 45°. Negative steps rotate counterclockwise.
+    pub fn rotate(&self, steps: i32) -> Direction {
+        Self::ALL_WITH_DIAGONALS[(self.index() as i32 + steps).rem_euclid(8) as usize]
+    }
+
+    /// Turns by 90° clockwise.
+    pub fn turn_right(&self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// Turns by 90° counterclockwise.
+    pub fn turn_left(&self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// Moves one step into this direction, if the position stays between 0 and `upper_limit` (inclusive).
+    pub fn walk_pos(
+        &self,
+        pos: (usize, usize),
+        upper_limit: (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let (dx, dy) = self.offset();
+        let new_pos = (pos.0.checked_add_signed(dx)?, pos.1.checked_add_signed(dy)?);
+
+        if (dx > 0 && new_pos.0 > upper_limit.0) || (dy > 0 && new_pos.1 > upper_limit.1) {
+            return None;
+        }
+        Some(new_pos)
+    }
+
+    /// Moves one step into this direction, if the position stays between the limits (inclusive).
+    pub fn walk_pos_signed(
+        &self,
+        pos: (isize, isize),
+        lower_limit: (isize, isize),
+        upper_limit: (isize, isize),
+    ) -> Option<(isize, isize)> {
+        let (dx, dy) = self.offset();
+        let new_pos = (pos.0 + dx, pos.1 + dy);
+
+        if (dx < 0 && new_pos.0 < lower_limit.0)
+            || (dy < 0 && new_pos.1 < lower_limit.1)
+            || (dx > 0 && new_pos.0 > upper_limit.0)
+            || (dy > 0 && new_pos.1 > upper_limit.1)
+        {
+            return None;
+        }
+        Some(new_pos)
+    }
+}
+
+/// A small, seedable pseudo random number generator, using the SplitMix64 algorithm.
+///
+/// It is not suitable for cryptography, but produces the same sequence on every platform,
+/// which makes generated inputs reproducible from their seed.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number within the inclusive range.
+    pub fn range(&mut self, range: std::ops::RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Empty range.");
+
+        match (end - start).checked_add(1) {
+            // multiplying instead of using the remainder avoids most of the modulo bias.
+            Some(span) => start + ((self.next_u64() as u128 * span as u128) >> 64) as u64,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Returns a number within the inclusive range.
+    pub fn range_usize(&mut self, range: std::ops::RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        self.range(start as u64..=end as u64) as usize
+    }
+
+    /// Returns true with the specified probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64) < probability
+    }
+
+    /// Returns a random element of a non-empty slice.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range_usize(0..=items.len() - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction_constants() {
+        assert!(Direction::ALL.iter().all(|v| !v.is_diagonal()));
+        assert!(Direction::DIAGONAL.iter().all(|v| v.is_diagonal()));
+        assert_eq!(Direction::ALL_WITH_DIAGONALS.len(), 8);
+        for direction in Direction::ALL.iter().chain(&Direction::DIAGONAL) {
+            assert!(Direction::ALL_WITH_DIAGONALS.contains(direction));
+        }
+    }
+
+    #[test]
+    fn direction_turns() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::West.turn_right(), Direction::North);
+        assert_eq!(Direction::NorthEast.rotate(-1), Direction::North);
+        assert_eq!(Direction::North.rotate(-3), Direction::SouthWest);
+        assert_eq!(Direction::SouthEast.opposite(), Direction::NorthWest);
+        for direction in Direction::ALL_WITH_DIAGONALS {
+            assert_eq!(direction.rotate(8), direction);
+            let (dx, dy) = direction.offset();
+            assert_eq!(direction.opposite().offset(), (-dx, -dy));
+        }
+    }
+
+    #[test]
+    fn direction_walks_within_limits() {
+        assert_eq!(Direction::North.walk_pos((1, 0), (2, 2)), None);
+        assert_eq!(Direction::East.walk_pos((2, 1), (2, 2)), None);
+        assert_eq!(Direction::SouthWest.walk_pos((1, 1), (2, 2)), Some((0, 2)));
+        assert_eq!(Direction::SouthWest.walk_pos((0, 1), (2, 2)), None);
+        assert_eq!(
+            Direction::NorthWest.walk_pos_signed((0, 0), (-1, -1), (1, 1)),
+            Some((-1, -1))
+        );
+        assert_eq!(
+            Direction::West.walk_pos_signed((-1, 0), (-1, -1), (1, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn point_variants_match_positions() {
+        let map = Map::from_str_with("ab\ncd", |c| c);
+        let corner = Point2::new(0, 0);
+
+        assert_eq!(map.find_point(|&c| c == 'c'), Some(Point2::new(0, 1)));
+        assert_eq!(map.find_point(|&c| c == 'x'), None);
+        assert_eq!(
+            map.find_all_points(|&c| c != 'a').collect::<Vec<_>>(),
+            vec![Point2::new(1, 0), Point2::new(0, 1), Point2::new(1, 1)]
+        );
+        assert_eq!(
+            map.points().collect::<Vec<_>>(),
+            map.iter_points().map(|(v, _)| v).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            map.neighbours4_point(corner).collect::<Vec<_>>(),
+            vec![(Point2::new(1, 0), &'b'), (Point2::new(0, 1), &'c')]
+        );
+        assert_eq!(map.neighbours8_point(corner).count(), 3);
+        assert_eq!(map[Point2::from([1, 1])], 'd');
+    }
+
+    fn grid() -> Map<char> {
+        Map::from_str_with("abc\ndef\nghi", |c| c)
+    }
+
+    #[test]
+    fn parsing_and_rendering() {
+        let map = grid();
+        assert_eq!(map.dimensions(), (3, 3));
+        assert_eq!(map.to_string(), "abc\ndef\nghi");
+
+        let digits = Map::from_str_with("12\n34\n", |c| c.to_digit(10).unwrap());
+        assert_eq!(digits.dimensions(), (2, 2));
+        assert_eq!(digits.get(1, 1), Some(&4));
+        assert_eq!(digits.to_string(), "12\n34");
+
+        assert_eq!(
+            Map::<char>::from("ab\ncd"),
+            Map::from_str_with("ab\ncd", |c| c)
+        );
+        assert_eq!(Map::from_str_with("", |c| c).dimensions(), (0, 0));
+        assert_eq!(Map::from_str_with("", |c| c).to_string(), "");
+
+        let filled = Map::filled(2, 3, '.');
+        assert_eq!(filled.dimensions(), (2, 3));
+        assert_eq!(filled.to_string(), "..\n..\n..");
+    }
+
+    #[test]
+    fn signed_and_bounds_checked_access() {
+        let mut map = grid();
+        assert_eq!(map.get_signed(2, 1), Some(&'f'));
+        assert_eq!(map.get_signed(-1, 0), None);
+        assert_eq!(map.get_signed(0, -1), None);
+        assert_eq!(map.get_signed(3, 0), None);
+        assert_eq!(map.get(0, 3), None);
+        assert!(map.contains(2, 2));
+        assert!(!map.contains(3, 2));
+        assert!(!map.contains(2, 3));
+
+        map.set(1, 1, 'x');
+        assert_eq!(map.get(1, 1), Some(&'x'));
+    }
+
+    #[test]
+    fn positions_and_searching() {
+        let map = grid();
+        let positions: Vec<_> = map.positions().collect();
+        assert_eq!(positions.len(), 9);
+        assert_eq!(&positions[..4], &[(0, 0), (1, 0), (2, 0), (0, 1)]);
+        assert_eq!(
+            map.iter().map(|(position, _)| position).collect::<Vec<_>>(),
+            positions
+        );
+        assert_eq!(map.iter().map(|(_, c)| c).collect::<String>(), "abcdefghi");
+
+        assert_eq!(map.find(|&c| c == 'e'), Some((1, 1)));
+        assert_eq!(map.find(|&c| c > 'b'), Some((2, 0)));
+        assert_eq!(map.find(|&c| c == 'z'), None);
+        assert_eq!(
+            map.find_all(|&c| "aei".contains(c)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1), (2, 2)]
+        );
+        assert_eq!(map.find_all(|&c| c == 'z').count(), 0);
+    }
+
+    #[test]
+    fn neighbours_within_the_map() {
+        let map = grid();
+        let tiles = |neighbours: Vec<((usize, usize), &char)>| -> String {
+            neighbours.into_iter().map(|(_, c)| *c).collect()
+        };
+
+        assert_eq!(tiles(map.neighbours4(1, 1).collect()), "bfhd");
+        assert_eq!(tiles(map.neighbours8(1, 1).collect()), "abcfihgd");
+
+        // Corners and edges only have the neighbours inside the map.
+        assert_eq!(tiles(map.neighbours4(0, 0).collect()), "bd");
+        assert_eq!(tiles(map.neighbours8(0, 0).collect()), "bed");
+        assert_eq!(tiles(map.neighbours4(2, 2).collect()), "fh");
+        assert_eq!(tiles(map.neighbours8(2, 2).collect()), "efh");
+        assert_eq!(tiles(map.neighbours4(2, 1).collect()), "cie");
+        assert_eq!(tiles(map.neighbours8(1, 0).collect()), "cfeda");
+        assert_eq!(
+            map.neighbours8(1, 0).find(|(_, c)| **c == 'e'),
+            Some(((1, 1), &'e'))
+        );
+
+        // Positions outside the map have no neighbours inside it, unless they touch it.
+        assert_eq!(map.neighbours8(4, 4).count(), 0);
+        assert_eq!(tiles(map.neighbours8(3, 3).collect()), "i");
+
+        let single = Map::filled(1, 1, 'x');
+        assert_eq!(single.neighbours8(0, 0).count(), 0);
+    }
+}
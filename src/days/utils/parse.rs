@@ -0,0 +1,346 @@
+//! Helpers for parsing inputs using regular expressions.
+//!
+//! Patterns always have to match a whole line (or list item), and their capture groups are converted
+//! to typed values using [`FromCaptures`], which is implemented for tuples of [`FromStr`] types:
+//!
+//! ```ignore
+//! let ranges: Vec<(u64, u64)> = parse_lines(input, r"(\d+)-(\d+)")?;
+//! ```
+//!
+//! On a mismatch, the returned [`ParseError`] contains the failing line.
+
+use crate::days::InputProblem;
+use regex::{Captures, Regex};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A line which didn't match the expected pattern, or whose captures couldn't be converted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The index of the line, starting at 0.
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, text: &str, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            text: text.to_owned(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: {} in `{}`",
+            self.line + 1,
+            self.message,
+            self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for InputProblem {
+    fn from(value: ParseError) -> Self {
+        InputProblem::at(value.line, format!("{} in `{}`", value.message, value.text))
+    }
+}
+
+/// Types which can be created from the capture groups of a regex match.
+pub trait FromCaptures: Sized {
+    fn from_captures(captures: &Captures) -> Result<Self, String>;
+}
+
+/// Converts a single capture group, starting at 1.
+pub fn capture<T: FromStr>(captures: &Captures, group: usize) -> Result<T, String> {
+    let value = captures
+        .get(group)
+        .ok_or_else(|| format!("group {} didn't match", group))?
+        .as_str();
+
+    value
+        .parse()
+        .map_err(|_| format!("`{}` is not a valid {}", value, std::any::type_name::<T>()))
+}
+
+macro_rules! impl_from_captures {
+    ($(($($t:ident $i:literal),+)),*) => {$(
+        impl<$($t: FromStr),+> FromCaptures for ($($t,)+) {
+            fn from_captures(captures: &Captures) -> Result<Self, String> {
+                Ok(($(capture::<$t>(captures, $i)?,)+))
+            }
+        }
+    )*};
+}
+
+impl_from_captures!(
+    (A 1),
+    (A 1, B 2),
+    (A 1, B 2, C 3),
+    (A 1, B 2, C 3, D 4),
+    (A 1, B 2, C 3, D 4, E 5),
+    (A 1, B 2, C 3, D 4, E 5, F 6),
+    (A 1, B 2, C 3, D 4, E 5, F 6, G 7),
+    (A 1, B 2, C 3, D 4, E 5, F 6, G 7, H 8)
+);
+
+/// Compiles a pattern which has to match the whole text.
+fn anchored(pattern: &str) -> Regex {
+    Regex::new(&format!("^(?:{})$", pattern)).expect("Invalid pattern")
+}
+
+/// Matches a single line against a compiled pattern, and converts the captures.
+fn parse_with<T>(
+    regex: &Regex,
+    line: usize,
+    text: &str,
+    f: &mut impl FnMut(&Captures) -> Result<T, String>,
+) -> Result<T, ParseError> {
+    let captures = regex
+        .captures(text)
+        .ok_or_else(|| ParseError::new(line, text, format!("doesn't match `{}`", regex)))?;
+    f(&captures).map_err(|message| ParseError::new(line, text, message))
+}
+
+/// Matches every line against the pattern, converting the captures using `f`.
+pub fn parse_lines_with<T>(
+    input: &str,
+    pattern: &str,
+    mut f: impl FnMut(&Captures) -> Result<T, String>,
+) -> Result<Vec<T>, ParseError> {
+    Section {
+        line: 0,
+        text: input,
+    }
+    .parse_lines_with(pattern, &mut f)
+}
+
+/// Matches every line against the pattern, and converts the capture groups to `T`.
+pub fn parse_lines<T: FromCaptures>(input: &str, pattern: &str) -> Result<Vec<T>, ParseError> {
+    parse_lines_with(input, pattern, T::from_captures)
+}
+
+/// Splits a single line at `separator`, and matches every item against the pattern.
+pub fn parse_list<T: FromCaptures>(
+    line: &str,
+    separator: &str,
+    pattern: &str,
+) -> Result<Vec<T>, ParseError> {
+    let regex = anchored(pattern);
+    line.split(separator)
+        .map(|item| parse_with(&regex, 0, item.trim(), &mut T::from_captures))
+        .collect()
+}
+
+/// Splits a single line at commas, and parses every item as `T`.
+pub fn parse_comma_list<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError> {
+    Ok(parse_list::<(T,)>(line, ",", r"(.*)")?
+        .into_iter()
+        .map(|(v,)| v)
+        .collect())
+}
+
+/// A part of the input, remembering at which line it starts, so errors report the line within the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Section<'a> {
+    /// The index of the first line of the section within the input, starting at 0.
+    pub line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> + use<'a> {
+        let start = self.line;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(i, v)| (start + i, v))
+    }
+
+    /// Matches every line of the section against the pattern, converting the captures using `f`.
+    pub fn parse_lines_with<T>(
+        &self,
+        pattern: &str,
+        mut f: impl FnMut(&Captures) -> Result<T, String>,
+    ) -> Result<Vec<T>, ParseError> {
+        let regex = anchored(pattern);
+        self.lines()
+            .map(|(line, text)| parse_with(&regex, line, text, &mut f))
+            .collect()
+    }
+
+    /// Matches every line of the section against the pattern, and converts the capture groups to `T`.
+    pub fn parse_lines<T: FromCaptures>(&self, pattern: &str) -> Result<Vec<T>, ParseError> {
+        self.parse_lines_with(pattern, T::from_captures)
+    }
+
+    /// Matches the first line of a labelled section, like `Tile 1234:`, against the pattern.
+    ///
+    /// Returns the converted captures and the remaining lines of the section.
+    pub fn labelled<T: FromCaptures>(&self, pattern: &str) -> Result<(T, Section<'a>), ParseError> {
+        let (label, body) = self.text.split_once('\n').unwrap_or((self.text, ""));
+        let label = parse_with(&anchored(pattern), self.line, label, &mut T::from_captures)?;
+
+        Ok((
+            label,
+            Section {
+                line: self.line + 1,
+                text: body,
+            },
+        ))
+    }
+}
+
+/// Splits the input into sections separated by blank lines.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+
+    for (i, line) in input.split('\n').enumerate() {
+        let end = offset + line.len();
+        match (line.trim_end_matches('\r').is_empty(), start) {
+            (true, Some((line_index, byte))) => {
+                sections.push(Section {
+                    line: line_index,
+                    text: input[byte..offset].trim_end_matches(['\r', '\n']),
+                });
+                start = None;
+            }
+            (false, None) => start = Some((i, offset)),
+            _ => {}
+        }
+        offset = end + 1;
+    }
+    if let Some((line, byte)) = start {
+        sections.push(Section {
+            line,
+            text: input[byte..].trim_end_matches(['\r', '\n']),
+        });
+    }
+
+    sections
+}
+
+/// Splits the input into sections separated by blank lines, and matches every line against the pattern.
+pub fn parse_sections<T: FromCaptures>(
+    input: &str,
+    pattern: &str,
+) -> Result<Vec<Vec<T>>, ParseError> {
+    sections(input)
+        .iter()
+        .map(|section| section.parse_lines(pattern))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_converted_to_tuples() {
+        let ranges: Vec<(u64, u64)> = parse_lines("1-3\n10-20", r"(\d+)-(\d+)").unwrap();
+        assert_eq!(ranges, vec![(1, 3), (10, 20)]);
+
+        let moves: Vec<(char, i32)> = parse_lines("L5\nR-2", r"([LR])(-?\d+)").unwrap();
+        assert_eq!(moves, vec![('L', 5), ('R', -2)]);
+
+        let lengths = parse_lines_with("ab\ncde", r"(\w+)", |captures| Ok(captures[1].len()));
+        assert_eq!(lengths, Ok(vec![2, 3]));
+    }
+
+    #[test]
+    fn patterns_have_to_match_the_whole_line() {
+        let error = parse_lines::<(u32,)>("12\n3x4", r"(\d+)").unwrap_err();
+        assert_eq!(error.line, 1);
+        assert_eq!(error.text, "3x4");
+        assert_eq!(
+            error.to_string(),
+            r"line 2: doesn't match `^(?:(\d+))$` in `3x4`"
+        );
+    }
+
+    #[test]
+    fn conversion_errors_name_the_value_and_type() {
+        let error = parse_lines::<(u8,)>("300", r"(\d+)").unwrap_err();
+        assert_eq!(error.message, "`300` is not a valid u8");
+
+        let error = parse_lines::<(u8, u8)>("7", r"(\d)(\d)?").unwrap_err();
+        assert_eq!(error.message, "group 2 didn't match");
+
+        let problem = InputProblem::from(error);
+        assert_eq!(problem.line, Some(1));
+        assert_eq!(problem.message, "group 2 didn't match in `7`");
+    }
+
+    #[test]
+    fn lists_are_split_and_trimmed() {
+        assert_eq!(parse_comma_list::<i32>("1, -2,3"), Ok(vec![1, -2, 3]));
+        assert!(parse_comma_list::<i32>("1,,3").is_err());
+
+        let pairs: Vec<(u32, u32)> = parse_list("1x2; 3x4", ";", r"(\d+)x(\d+)").unwrap();
+        assert_eq!(pairs, vec![(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn sections_keep_their_line_numbers() {
+        let input = "a\nb\n\n\nc\r\n\r\nd\n";
+        let sections = sections(input);
+        assert_eq!(
+            sections,
+            vec![
+                Section {
+                    line: 0,
+                    text: "a\nb"
+                },
+                Section { line: 4, text: "c" },
+                Section { line: 6, text: "d" },
+            ]
+        );
+        assert_eq!(
+            sections[0].lines().collect::<Vec<_>>(),
+            vec![(0, "a"), (1, "b")]
+        );
+        assert!(super::sections("").is_empty());
+
+        // Errors within a section report the line within the whole input.
+        let error = parse_sections::<(u32,)>("1\n2\n\n3\nx", r"(\d+)").unwrap_err();
+        assert_eq!(error.line, 4);
+        assert_eq!(
+            parse_sections::<(u32,)>("1\n2\n\n3", r"(\d+)"),
+            Ok(vec![vec![(1,), (2,)], vec![(3,)]])
+        );
+    }
+
+    #[test]
+    fn labelled_sections() {
+        let section = sections("x\n\nTile 12:\n#.\n.#")[1];
+        let ((id,), body): ((u32,), _) = section.labelled(r"Tile (\d+):").unwrap();
+        assert_eq!(id, 12);
+        assert_eq!(
+            body,
+            Section {
+                line: 3,
+                text: "#.\n.#"
+            }
+        );
+
+        let error = section.labelled::<(u32,)>(r"Block (\d+):").unwrap_err();
+        assert_eq!(error.line, 2);
+
+        let (_, empty) = Section {
+            line: 0,
+            text: "Tile 1:",
+        }
+        .labelled::<(u32,)>(r"Tile (\d+):")
+        .unwrap();
+        assert_eq!(empty.lines().count(), 0);
+    }
+}
@@ -0,0 +1,383 @@
+//! Shortest paths on a [`Map`], moving between orthogonally adjacent tiles.
+//!
+//! All searches support multiple start positions, and return a [`GridPaths`] containing the
+//! distance and predecessor of every reached tile, from which the paths can be reconstructed.
+//! Every function taking or returning positions has a `_point` variant using [`Point2`] instead.
+
+use super::{Direction, Map, Point2, search};
+use std::collections::VecDeque;
+
+/// The result of a search: distances and predecessors of every tile reached from the start positions.
+#[derive(Debug, Clone)]
+pub struct GridPaths<C> {
+    distances: Map<Option<C>>,
+    predecessors: Map<Option<(usize, usize)>>,
+}
+
+impl<C> GridPaths<C>
+where
+    C: Copy,
+{
+    fn new(dimensions: (usize, usize)) -> Self {
+        Self {
+            distances: Map::filled(dimensions.0, dimensions.1, None),
+            predecessors: Map::filled(dimensions.0, dimensions.1, None),
+        }
+    }
+
+    /// The distance from the nearest start position, or None if the position wasn't reached.
+    pub fn distance(&self, position: (usize, usize)) -> Option<C> {
+        *self.distances.get(position.0, position.1)?
+    }
+
+    /// The previous tile on the shortest path to the position. Start positions have no predecessor.
+    pub fn predecessor(&self, position: (usize, usize)) -> Option<(usize, usize)> {
+        *self.predecessors.get(position.0, position.1)?
+    }
+
+    /// The distances of all tiles, with None for unreached tiles.
+    pub fn distances(&self) -> &Map<Option<C>> {
+        &self.distances
+    }
+
+    /// The predecessors of all tiles, with None for start positions and unreached tiles.
+    pub fn predecessors(&self) -> &Map<Option<(usize, usize)>> {
+        &self.predecessors
+    }
+
+    /// Iterates over all reached positions and their distances, line by line.
+    pub fn reached(&self) -> impl Iterator<Item = ((usize, usize), C)> {
+        self.distances
+            .iter()
+            .filter_map(|(position, distance)| Some((position, (*distance)?)))
+    }
+
+    /// Reconstructs the shortest path from a start position to the position, including both ends.
+    ///
+    /// Returns None if the position wasn't reached.
+    pub fn path_to(&self, position: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        self.distance(position)?;
+
+        let mut path = vec![position];
+        while let Some(previous) = self.predecessor(*path.last().unwrap()) {
+            path.push(previous);
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// The distance of the point from the nearest start point, or None if the point wasn't reached.
+    pub fn distance_point(&self, point: Point2<usize>) -> Option<C> {
+        self.distance(point.into())
+    }
+
+    /// The previous point on the shortest path to the point. Start points have no predecessor.
+    pub fn predecessor_point(&self, point: Point2<usize>) -> Option<Point2<usize>> {
+        self.predecessor(point.into()).map(Point2::from)
+    }
+
+    /// Iterates over all reached points and their distances, line by line.
+    pub fn reached_points(&self) -> impl Iterator<Item = (Point2<usize>, C)> {
+        self.reached()
+            .map(|(position, distance)| (position.into(), distance))
+    }
+
+    /// Reconstructs the shortest path from a start point to the point, including both ends.
+    ///
+    /// Returns None if the point wasn't reached.
+    pub fn path_to_point(&self, point: Point2<usize>) -> Option<Vec<Point2<usize>>> {
+        Some(
+            self.path_to(point.into())?
+                .into_iter()
+                .map(Point2::from)
+                .collect(),
+        )
+    }
+}
+
+/// Iterates over the positions orthogonally adjacent to `position` which are within the map.
+fn adjacent<T>(map: &Map<T>, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    let (width, height) = map.dimensions();
+    let upper_limit = (width.saturating_sub(1), height.saturating_sub(1));
+
+    Direction::ALL
+        .into_iter()
+        .filter_map(move |direction| direction.walk_pos(position, upper_limit))
+}
+
+/// Finds the shortest paths from the start positions to all reachable tiles, where every step costs 1.
+///
+/// Only tiles for which `passable` returns true are entered. Start positions are always reached.
+pub fn bfs<T>(
+    map: &Map<T>,
+    starts: impl IntoIterator<Item = (usize, usize)>,
+    mut passable: impl FnMut(&T) -> bool,
+) -> GridPaths<usize> {
+    let mut paths = GridPaths::new(map.dimensions());
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if map.contains(start.0, start.1) && paths.distance(start).is_none() {
+            paths.distances.set(start.0, start.1, Some(0));
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(position) = queue.pop_front() {
+        let distance = paths.distance(position).unwrap();
+
+        for next in adjacent(map, position) {
+            if paths.distance(next).is_some() || !passable(map.get(next.0, next.1).unwrap()) {
+                continue;
+            }
+
+            paths.distances.set(next.0, next.1, Some(distance + 1));
+            paths.predecessors.set(next.0, next.1, Some(position));
+            queue.push_back(next);
+        }
+    }
+
+    paths
+}
+
+/// Shared implementation of [`dijkstra`] and [`a_star`], running [`search::weighted_search`] over the
+/// positions of the map. Stops once `goal` is taken from the queue, if given.
+fn weighted_search<T>(
+    map: &Map<T>,
+    starts: impl IntoIterator<Item = (usize, usize)>,
+    goal: Option<(usize, usize)>,
+    mut cost: impl FnMut((usize, usize), &T) -> Option<u64>,
+    heuristic: impl FnMut(&(usize, usize)) -> u64,
+) -> GridPaths<u64> {
+    let starts = starts
+        .into_iter()
+        .filter(|start| map.contains(start.0, start.1));
+    let successors = |position: &(usize, usize)| {
+        adjacent(map, *position)
+            .filter_map(|next| Some((next, cost(next, map.get(next.0, next.1).unwrap())?)))
+            .collect::<Vec<_>>()
+    };
+    let (found, _) = search::weighted_search(
+        starts,
+        successors,
+        |position| Some(*position) == goal,
+        heuristic,
+    );
+
+    let mut paths = GridPaths::new(map.dimensions());
+    for (&position, distance) in found.reached() {
+        paths.distances.set(position.0, position.1, Some(distance));
+        if let Some(&previous) = found.predecessor(&position) {
+            paths
+                .predecessors
+                .set(position.0, position.1, Some(previous));
+        }
+    }
+
+    paths
+}
+
+/// Finds the cheapest paths from the start positions to all reachable tiles.
+///
+/// `cost` returns the cost of entering a tile, or None if it can't be entered.
+pub fn dijkstra<T>(
+    map: &Map<T>,
+    starts: impl IntoIterator<Item = (usize, usize)>,
+    cost: impl FnMut((usize, usize), &T) -> Option<u64>,
+) -> GridPaths<u64> {
+    weighted_search(map, starts, None, cost, |_| 0)
+}
+
+/// Finds the cheapest path from the start positions to `goal`, guided by a heuristic.
+///
+/// `cost` returns the cost of entering a tile, or None if it can't be entered.
+/// `heuristic` estimates the remaining cost from a position to the goal, and must never overestimate it,
+/// for example the [`manhattan_distance`] if every step costs at least 1. Tiles are explored again if a
+/// cheaper path to them is found, which only happens if the heuristic isn't consistent.
+///
+/// The search stops once the goal is reached, so the result only contains the tiles expanded until then,
+/// not those still waiting in the queue. Their distances are the shortest if the heuristic is consistent,
+/// i.e. never drops by more than the cost of a step. Otherwise, only the goal's distance and path are.
+pub fn a_star<T>(
+    map: &Map<T>,
+    starts: impl IntoIterator<Item = (usize, usize)>,
+    goal: (usize, usize),
+    cost: impl FnMut((usize, usize), &T) -> Option<u64>,
+    mut heuristic: impl FnMut((usize, usize)) -> u64,
+) -> GridPaths<u64> {
+    weighted_search(map, starts, Some(goal), cost, |position| {
+        heuristic(*position)
+    })
+}
+
+/// Like [`bfs`], but with [`Point2`] start points.
+pub fn bfs_point<T>(
+    map: &Map<T>,
+    starts: impl IntoIterator<Item = Point2<usize>>,
+    passable: impl FnMut(&T) -> bool,
+) -> GridPaths<usize> {
+    bfs(
+        map,
+        starts.into_iter().map(<(usize, usize)>::from),
+        passable,
+    )
+}
+
+/// Like [`dijkstra`], but with [`Point2`] start points, and the point of the tile passed to `cost`.
+pub fn dijkstra_point<T>(
+    map: &Map<T>,
+    starts: impl IntoIterator<Item = Point2<usize>>,
+    mut cost: impl FnMut(Point2<usize>, &T) -> Option<u64>,
+) -> GridPaths<u64> {
+    dijkstra(
+        map,
+        starts.into_iter().map(<(usize, usize)>::from),
+        |position, tile| cost(position.into(), tile),
+    )
+}
+
+/// Like [`a_star`], but with [`Point2`] start points and goal, and points passed to `cost` and `heuristic`.
+pub fn a_star_point<T>(
+    map: &Map<T>,
+    starts: impl IntoIterator<Item = Point2<usize>>,
+    goal: Point2<usize>,
+    mut cost: impl FnMut(Point2<usize>, &T) -> Option<u64>,
+    mut heuristic: impl FnMut(Point2<usize>) -> u64,
+) -> GridPaths<u64> {
+    a_star(
+        map,
+        starts.into_iter().map(<(usize, usize)>::from),
+        goal.into(),
+        |position, tile| cost(position.into(), tile),
+        |position| heuristic(position.into()),
+    )
+}
+
+/// The number of orthogonal steps between two positions.
+pub fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> u64 {
+    (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maze() -> Map<char> {
+        Map::from_str_with("...\n.#.\n...\n#..", |c| c)
+    }
+
+    #[test]
+    fn bfs_walks_around_walls() {
+        let paths = bfs(&maze(), [(0, 0)], |&c| c == '.');
+        assert_eq!(paths.distance((2, 2)), Some(4));
+        assert_eq!(paths.distance((1, 2)), Some(3));
+        assert_eq!(paths.distance((1, 1)), None);
+        assert_eq!(paths.distance((0, 3)), None);
+        assert_eq!(paths.distance((5, 5)), None);
+        assert_eq!(paths.predecessor((0, 0)), None);
+        assert_eq!(paths.reached().count(), 10);
+        assert_eq!(
+            paths.path_to((1, 3)).unwrap(),
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (1, 3)]
+        );
+        assert_eq!(paths.path_to((1, 1)), None);
+    }
+
+    #[test]
+    fn bfs_from_multiple_starts() {
+        let paths = bfs(&maze(), [(0, 0), (2, 3), (9, 9)], |&c| c == '.');
+        assert_eq!(paths.distance((2, 3)), Some(0));
+        assert_eq!(paths.distance((2, 1)), Some(2));
+        assert_eq!(paths.distance((0, 2)), Some(2));
+    }
+
+    #[test]
+    fn dijkstra_and_a_star_avoid_expensive_tiles() {
+        let map = Map::from_str_with("19111\n11191\n99911", |c| c.to_digit(10).unwrap() as u64);
+        let cost = |_, &v: &u64| (v < 9).then_some(v);
+
+        let paths = dijkstra(&map, [(0, 0)], cost);
+        assert_eq!(paths.distance((4, 2)), Some(8));
+        assert_eq!(paths.distance((1, 0)), None);
+        assert_eq!(
+            paths.path_to((4, 0)).unwrap(),
+            vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0), (3, 0), (4, 0)]
+        );
+
+        let goal = (4, 2);
+        let paths = a_star(&map, [(0, 0)], goal, cost, |p| manhattan_distance(p, goal));
+        assert_eq!(paths.distance(goal), Some(8));
+        assert_eq!(paths.path_to(goal).unwrap().len(), 9);
+    }
+
+    #[test]
+    fn a_star_leaves_out_queued_tiles() {
+        let map = Map::from_str_with("111\n511", |c| c.to_digit(10).unwrap() as u64);
+        let goal = (2, 0);
+        let paths = a_star(&map, [(0, 0)], goal, |_, &v: &u64| Some(v), |_| 0);
+
+        // (0, 1) and (1, 1) were queued, but the goal was taken from the queue before them.
+        assert_eq!(
+            paths.reached().collect::<Vec<_>>(),
+            vec![((0, 0), 0), ((1, 0), 1), ((2, 0), 2)]
+        );
+        assert_eq!(paths.distance((0, 1)), None);
+        assert_eq!(paths.predecessor((1, 1)), None);
+    }
+
+    #[test]
+    fn a_star_reopens_tiles_with_inconsistent_heuristic() {
+        // Overrating (1, 0) makes the search expand (2, 0) through the detour along the bottom first.
+        let map = Map::from_str_with("1113\n1119", |c| c.to_digit(10).unwrap() as u64);
+        let goal = (3, 0);
+        let heuristic = |p| if p == (1, 0) { 4 } else { 0 };
+
+        let paths = a_star(
+            &map,
+            [(0, 0)],
+            goal,
+            |_, &v: &u64| (v < 9).then_some(v),
+            heuristic,
+        );
+        assert_eq!(paths.distance(goal), Some(5));
+        assert_eq!(
+            paths.path_to(goal).unwrap(),
+            vec![(0, 0), (1, 0), (2, 0), (3, 0)]
+        );
+    }
+
+    #[test]
+    fn point_variants_match_positions() {
+        let map = maze();
+        let start = Point2::new(0, 0);
+        let goal = Point2::new(2, 3);
+        let cost = |_, &c: &char| (c == '.').then_some(1);
+
+        let paths = bfs_point(&map, [start], |&c| c == '.');
+        assert_eq!(paths.distance_point(goal), Some(5));
+        assert_eq!(paths.predecessor_point(Point2::new(1, 0)), Some(start));
+        assert_eq!(paths.reached_points().count(), paths.reached().count());
+        assert_eq!(
+            paths.path_to_point(goal).unwrap(),
+            paths
+                .path_to((2, 3))
+                .unwrap()
+                .into_iter()
+                .map(Point2::from)
+                .collect::<Vec<_>>()
+        );
+
+        let paths = dijkstra_point(&map, [start], cost);
+        assert_eq!(paths.distance_point(goal), Some(5));
+        let paths = a_star_point(&map, [start], goal, cost, |p| p.manhattan_distance(goal));
+        assert_eq!(paths.distance_point(goal), Some(5));
+    }
+
+    #[test]
+    fn manhattan_distance_is_symmetric() {
+        assert_eq!(manhattan_distance((1, 5), (4, 1)), 7);
+        assert_eq!(manhattan_distance((4, 1), (1, 5)), 7);
+        assert_eq!(manhattan_distance((2, 2), (2, 2)), 0);
+    }
+}
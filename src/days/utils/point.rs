@@ -0,0 +1,363 @@
+//! 2D and 3D points, which double as vectors between them.
+
+use super::Direction;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or vector in 2-Dimensional space. `y` grows downwards, like the lines of an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A position or vector in 3-Dimensional space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl Point2<usize> {
+    pub fn signed(self) -> Point2<isize> {
+        Point2::new(self.x as isize, self.y as isize)
+    }
+
+    /// Moves one step into the direction, returning None if a coordinate would become negative.
+    pub fn step(self, direction: Direction) -> Option<Self> {
+        let (dx, dy) = direction.offset();
+        Some(Self::new(
+            self.x.checked_add_signed(dx)?,
+            self.y.checked_add_signed(dy)?,
+        ))
+    }
+}
+
+impl Point2<isize> {
+    /// Converts to unsigned coordinates, returning None if a coordinate is negative.
+    pub fn unsigned(self) -> Option<Point2<usize>> {
+        Some(Point2::new(
+            self.x.try_into().ok()?,
+            self.y.try_into().ok()?,
+        ))
+    }
+
+    /// Moves one step into the direction.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction
+    }
+
+    /// Rotates the vector by 90° clockwise, keeping in mind that `y` grows downwards.
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates the vector by 90° counterclockwise, keeping in mind that `y` grows downwards.
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl Add<Direction> for Point2<isize> {
+    type Output = Self;
+
+    fn add(self, rhs: Direction) -> Self {
+        let (dx, dy) = rhs.offset();
+        Self::new(self.x + dx, self.y + dy)
+    }
+}
+
+macro_rules! impl_distances {
+    ($($t:ty),*) => {$(
+        impl Point2<$t> {
+            /// The number of orthogonal steps between two points.
+            pub fn manhattan_distance(self, other: Self) -> u64 {
+                (self.x.abs_diff(other.x) + self.y.abs_diff(other.y)) as u64
+            }
+
+            /// The number of steps between two points, if diagonal steps are allowed.
+            pub fn chebyshev_distance(self, other: Self) -> u64 {
+                self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)) as u64
+            }
+
+            /// The straight-line distance between two points.
+            pub fn euclidean_distance(self, other: Self) -> f64 {
+                let (dx, dy) = (self.x.abs_diff(other.x) as f64, self.y.abs_diff(other.y) as f64);
+                (dx * dx + dy * dy).sqrt()
+            }
+        }
+
+        impl Point3<$t> {
+            /// The number of orthogonal steps between two points.
+            pub fn manhattan_distance(self, other: Self) -> u64 {
+                (self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z))
+                    as u64
+            }
+
+            /// The number of steps between two points, if diagonal steps are allowed.
+            pub fn chebyshev_distance(self, other: Self) -> u64 {
+                self.x
+                    .abs_diff(other.x)
+                    .max(self.y.abs_diff(other.y))
+                    .max(self.z.abs_diff(other.z)) as u64
+            }
+
+            /// The straight-line distance between two points.
+            pub fn euclidean_distance(self, other: Self) -> f64 {
+                let (dx, dy, dz) = (
+                    self.x.abs_diff(other.x) as f64,
+                    self.y.abs_diff(other.y) as f64,
+                    self.z.abs_diff(other.z) as f64,
+                );
+                (dx * dx + dy * dy + dz * dz).sqrt()
+            }
+        }
+    )*};
+}
+
+impl_distances!(usize, isize, u32, i32, u64, i64);
+
+impl<T> Add for Point2<T>
+where
+    T: Add<Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T> Sub for Point2<T>
+where
+    T: Sub<Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T> Mul<T> for Point2<T>
+where
+    T: Mul<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T> Neg for Point2<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T> AddAssign for Point2<T>
+where
+    T: AddAssign,
+{
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T> SubAssign for Point2<T>
+where
+    T: SubAssign,
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T> Add for Point3<T>
+where
+    T: Add<Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T> Sub for Point3<T>
+where
+    T: Sub<Output = T>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T> Mul<T> for Point3<T>
+where
+    T: Mul<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T> Neg for Point3<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T> AddAssign for Point3<T>
+where
+    T: AddAssign,
+{
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T> SubAssign for Point3<T>
+where
+    T: SubAssign,
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from(value: (T, T)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(value: Point2<T>) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl<T> From<[T; 2]> for Point2<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from(value: (T, T, T)) -> Self {
+        Self::new(value.0, value.1, value.2)
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(value: Point3<T>) -> Self {
+        (value.x, value.y, value.z)
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Point2::from((1, 2)), Point2::new(1, 2));
+        assert_eq!(Point2::from([3, 4]), Point2::new(3, 4));
+        assert_eq!(<(i32, i32)>::from(Point2::new(5, 6)), (5, 6));
+        assert_eq!(Point3::from([1, 2, 3]), Point3::new(1, 2, 3));
+        assert_eq!(<(i32, i32, i32)>::from(Point3::from((4, 5, 6))), (4, 5, 6));
+
+        assert_eq!(Point2::new(2_usize, 3).signed(), Point2::new(2, 3));
+        assert_eq!(Point2::new(2_isize, 0).unsigned(), Some(Point2::new(2, 0)));
+        assert_eq!(Point2::new(-1_isize, 0).unsigned(), None);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut a = Point2::new(1, 2);
+        let b = Point2::new(3, -4);
+        assert_eq!(a + b, Point2::new(4, -2));
+        assert_eq!(a - b, Point2::new(-2, 6));
+        assert_eq!(b * 2, Point2::new(6, -8));
+        assert_eq!(-b, Point2::new(-3, 4));
+        a += b;
+        assert_eq!(a, Point2::new(4, -2));
+        a -= b;
+        assert_eq!(a, Point2::new(1, 2));
+
+        let c = Point3::new(1, 2, 3);
+        assert_eq!(c + c * 2 - Point3::new(3, 3, 3), Point3::new(0, 3, 6));
+        assert_eq!(-c, Point3::new(-1, -2, -3));
+    }
+
+    #[test]
+    fn steps_and_rotations() {
+        let origin = Point2::new(0_usize, 0);
+        assert_eq!(origin.step(Direction::North), None);
+        assert_eq!(origin.step(Direction::West), None);
+        assert_eq!(origin.step(Direction::SouthEast), Some(Point2::new(1, 1)));
+
+        let p = Point2::new(0_isize, 0);
+        assert_eq!(p.step(Direction::North), Point2::new(0, -1));
+        assert_eq!(p + Direction::NorthWest, Point2::new(-1, -1));
+
+        // North, rotated clockwise, points East.
+        let north = Point2::new(0_isize, -1);
+        assert_eq!(north.rotate_right(), Point2::new(1, 0));
+        assert_eq!(north.rotate_left(), Point2::new(-1, 0));
+        assert_eq!(north.rotate_right().rotate_right(), -north);
+    }
+
+    #[test]
+    fn distances() {
+        let (a, b) = (Point2::new(1_i32, 5), Point2::new(4, 1));
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.chebyshev_distance(b), 4);
+        assert_eq!(a.euclidean_distance(b), 5.0);
+
+        let (c, d) = (Point3::new(0_usize, 0, 0), Point3::new(2, 3, 6));
+        assert_eq!(c.manhattan_distance(d), 11);
+        assert_eq!(c.chebyshev_distance(d), 6);
+        assert_eq!(d.euclidean_distance(c), 7.0);
+    }
+}
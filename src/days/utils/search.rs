@@ -0,0 +1,675 @@
+//! Searches over arbitrary states, like a tuple of lights, counters or positions.
+//!
+//! Unlike [`pathfinding`](super::pathfinding), the states don't need to be tiles of a [`Map`](super::Map).
+//! Instead, every search takes a closure returning the successors of a state, so the state space is
+//! only explored as far as needed. States need to be hashable, to remember which were already visited.
+//!
+//! Searches exploring everything reachable return [`SearchPaths`], while searches for a goal return
+//! the [`Path`] to the first goal found.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// The result of a search: costs and predecessors of every state reached from the start states.
+#[derive(Debug, Clone)]
+pub struct SearchPaths<S, C> {
+    costs: HashMap<S, C>,
+    predecessors: HashMap<S, S>,
+}
+
+impl<S, C> SearchPaths<S, C>
+where
+    S: Clone + Hash + Eq,
+    C: Copy,
+{
+    fn new() -> Self {
+        Self {
+            costs: HashMap::new(),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    /// The cost from the nearest start state, or None if the state wasn't reached.
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+
+    /// The previous state on the cheapest path to the state. Start states have no predecessor.
+    pub fn predecessor(&self, state: &S) -> Option<&S> {
+        self.predecessors.get(state)
+    }
+
+    /// Iterates over all reached states and their costs, in no particular order.
+    pub fn reached(&self) -> impl Iterator<Item = (&S, C)> {
+        self.costs.iter().map(|(state, cost)| (state, *cost))
+    }
+
+    /// The number of reached states.
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// Drops all states for which `keep` returns false.
+    fn retain(&mut self, mut keep: impl FnMut(&S) -> bool) {
+        self.costs.retain(|state, _| keep(state));
+        self.predecessors.retain(|state, _| keep(state));
+    }
+
+    /// Reconstructs the cheapest path from a start state to the state, including both ends.
+    ///
+    /// Returns None if the state wasn't reached.
+    pub fn path_to(&self, state: &S) -> Option<Path<S, C>> {
+        let cost = self.cost(state)?;
+
+        let mut states = vec![state.clone()];
+        while let Some(previous) = self.predecessor(states.last().unwrap()) {
+            states.push(previous.clone());
+        }
+        states.reverse();
+
+        Some(Path { cost, states })
+    }
+}
+
+/// A path found by a search, from a start state to a goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    /// The total cost of all steps.
+    pub cost: C,
+    /// All states of the path, including the start and the goal.
+    pub states: Vec<S>,
+}
+
+impl<S, C> Path<S, C> {
+    /// The first state of the path.
+    pub fn start(&self) -> &S {
+        self.states.first().unwrap()
+    }
+
+    /// The last state of the path.
+    pub fn goal(&self) -> &S {
+        self.states.last().unwrap()
+    }
+
+    /// The number of steps taken, which is one less than the number of states.
+    pub fn steps(&self) -> usize {
+        self.states.len() - 1
+    }
+}
+
+/// Shared implementation of [`bfs`] and [`bfs_to`]. Returns the first goal reached, if any.
+fn unweighted_search<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> (SearchPaths<S, usize>, Option<S>)
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+{
+    let mut paths = SearchPaths::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !paths.costs.contains_key(&start) {
+            paths.costs.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            return (paths, Some(state));
+        }
+        let cost = paths.costs[&state];
+
+        for next in successors(&state) {
+            if paths.costs.contains_key(&next) {
+                continue;
+            }
+
+            paths.costs.insert(next.clone(), cost + 1);
+            paths.predecessors.insert(next.clone(), state.clone());
+            queue.push_back(next);
+        }
+    }
+
+    (paths, None)
+}
+
+/// Finds the shortest paths from the start states to all reachable states, where every step costs 1.
+///
+/// Only terminates if finitely many states are reachable.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+) -> SearchPaths<S, usize>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+{
+    unweighted_search(starts, successors, |_| false).0
+}
+
+/// Finds the shortest path from the start states to the nearest state for which `is_goal` returns true,
+/// where every step costs 1.
+pub fn bfs_to<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+{
+    let (paths, goal) = unweighted_search(starts, successors, is_goal);
+    paths.path_to(&goal?)
+}
+
+/// Shared implementation of [`dijkstra`], [`dijkstra_to`] and [`a_star`], also used by the grid
+/// searches of [`pathfinding`](super::pathfinding). Returns the first goal taken from the queue, if any.
+///
+/// A state is expanded again whenever a cheaper path to it is found, so the goal's path is the cheapest
+/// as long as the heuristic never overestimates, even if it isn't consistent.
+///
+/// If the search stops at a goal, the paths only contain the expanded states, as the costs of states
+/// still waiting in the queue might not be the cheapest yet. The costs of the expanded states are the
+/// cheapest, unless the heuristic isn't consistent. Then only the goal's cost is guaranteed to be.
+pub(super) fn weighted_search<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    mut heuristic: impl FnMut(&S) -> u64,
+) -> (SearchPaths<S, u64>, Option<S>)
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut paths = SearchPaths::new();
+    let mut expanded = HashSet::new();
+    // The heap only stores indices into `states`, so the states don't need to be ordered.
+    let mut states = Vec::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        if !paths.costs.contains_key(&start) {
+            paths.costs.insert(start.clone(), 0);
+            queue.push(Reverse((heuristic(&start), 0, states.len())));
+            states.push(start);
+        }
+    }
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let state = states[index].clone();
+        // A cheaper path to the state was found after this entry was queued.
+        if paths.costs[&state] < cost {
+            continue;
+        }
+        expanded.insert(state.clone());
+
+        if is_goal(&state) {
+            paths.retain(|state| expanded.contains(state));
+            return (paths, Some(state));
+        }
+
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+
+            if paths.cost(&next).is_some_and(|v| v <= next_cost) {
+                continue;
+            }
+
+            paths.costs.insert(next.clone(), next_cost);
+            paths.predecessors.insert(next.clone(), state.clone());
+            queue.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                states.len(),
+            )));
+            states.push(next);
+        }
+    }
+
+    (paths, None)
+}
+
+/// Finds the cheapest paths from the start states to all reachable states.
+///
+/// `successors` returns the states reachable from a state, together with the cost of the step.
+/// Only terminates if finitely many states are reachable.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+) -> SearchPaths<S, u64>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    weighted_search(starts, successors, |_| false, |_| 0).0
+}
+
+/// Finds the cheapest path from the start states to a state for which `is_goal` returns true.
+///
+/// `successors` returns the states reachable from a state, together with the cost of the step.
+pub fn dijkstra_to<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, u64>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let (paths, goal) = weighted_search(starts, successors, is_goal, |_| 0);
+    paths.path_to(&goal?)
+}
+
+/// Finds the cheapest path from the start states to a state for which `is_goal` returns true,
+/// guided by a heuristic.
+///
+/// `successors` returns the states reachable from a state, together with the cost of the step.
+/// `heuristic` estimates the remaining cost from a state to the nearest goal, and must never
+/// overestimate it, otherwise the path found might not be the cheapest. If it is also consistent,
+/// i.e. never drops by more than the cost of a step, every state is expanded at most once.
+pub fn a_star<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+    heuristic: impl FnMut(&S) -> u64,
+) -> Option<Path<S, u64>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let (paths, goal) = weighted_search(starts, successors, is_goal, heuristic);
+    paths.path_to(&goal?)
+}
+
+/// Finds the shortest path from `start` to `goal` by searching from both ends at once,
+/// where every step costs 1.
+///
+/// `successors` returns the states reachable from a state, `predecessors` the states from which
+/// a state can be reached. For searches where every step can be reversed, they are the same.
+/// Explores far fewer states than [`bfs_to`] if the number of states grows quickly with the distance.
+pub fn bidirectional_bfs<S, I, J>(
+    start: S,
+    goal: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut predecessors: impl FnMut(&S) -> J,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+    J: IntoIterator<Item = S>,
+{
+    if start == goal {
+        return Some(Path {
+            cost: 0,
+            states: vec![start],
+        });
+    }
+
+    // For every visited state, its distance and the neighbouring state towards the start or the goal respectively.
+    let mut forward: HashMap<S, (usize, Option<S>)> = HashMap::from([(start.clone(), (0, None))]);
+    let mut backward: HashMap<S, (usize, Option<S>)> = HashMap::from([(goal.clone(), (0, None))]);
+    let mut forward_layer = vec![start];
+    let mut backward_layer = vec![goal];
+    let mut depths = (0, 0);
+
+    let meeting = loop {
+        if forward_layer.is_empty() || backward_layer.is_empty() {
+            return None;
+        }
+
+        // Always expand the smaller layer, so both searches grow evenly.
+        let expand_forward = forward_layer.len() <= backward_layer.len();
+        let (layer, visited, other, depth) = match expand_forward {
+            true => (&mut forward_layer, &mut forward, &backward, &mut depths.0),
+            false => (&mut backward_layer, &mut backward, &forward, &mut depths.1),
+        };
+        *depth += 1;
+
+        // The whole layer is expanded, and the meeting state closest to the other end is chosen,
+        // so the path found is a shortest one.
+        let mut next_layer = Vec::new();
+        let mut meeting: Option<(usize, S)> = None;
+        for state in layer.drain(..) {
+            let neighbours: Vec<S> = match expand_forward {
+                true => successors(&state).into_iter().collect(),
+                false => predecessors(&state).into_iter().collect(),
+            };
+
+            for next in neighbours {
+                if visited.contains_key(&next) {
+                    continue;
+                }
+                visited.insert(next.clone(), (*depth, Some(state.clone())));
+
+                if let Some((other_depth, _)) = other.get(&next)
+                    && meeting.as_ref().is_none_or(|(best, _)| other_depth < best)
+                {
+                    meeting = Some((*other_depth, next.clone()));
+                }
+                next_layer.push(next);
+            }
+        }
+        *layer = next_layer;
+
+        if let Some((_, meeting)) = meeting {
+            break meeting;
+        }
+    };
+
+    let mut states = vec![meeting.clone()];
+    while let Some((_, Some(previous))) = forward.get(states.last().unwrap()) {
+        states.push(previous.clone());
+    }
+    states.reverse();
+    while let Some((_, Some(next))) = backward.get(states.last().unwrap()) {
+        states.push(next.clone());
+    }
+
+    Some(Path {
+        cost: states.len() - 1,
+        states,
+    })
+}
+
+/// Searches for a state for which `is_goal` returns true, only keeping the `width` most promising
+/// states of every step.
+///
+/// `successors` returns the states reachable from a state, together with the cost of the step.
+/// States are ranked by their cost plus the `heuristic`, lower is better. Returns the cheapest goal
+/// of the first step which reaches one, or None if all states were discarded before reaching a goal.
+///
+/// Much faster than [`a_star`] for huge state spaces, but the path found might not be the cheapest.
+pub fn beam_search<S, I>(
+    start: S,
+    width: usize,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    mut heuristic: impl FnMut(&S) -> u64,
+) -> Option<Path<S, u64>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    // Every state of the beams, with its cost and the index of its predecessor.
+    let mut states: Vec<(S, u64, Option<usize>)> = vec![(start, 0, None)];
+    let mut beam = vec![0];
+    let mut visited: HashSet<S> = HashSet::from([states[0].0.clone()]);
+
+    let goal = loop {
+        if let Some(goal) = beam
+            .iter()
+            .copied()
+            .filter(|&index| is_goal(&states[index].0))
+            .min_by_key(|&index| states[index].1)
+        {
+            break goal;
+        }
+
+        let mut candidates = Vec::new();
+        for &index in &beam {
+            let (state, cost, _) = &states[index];
+            let cost = *cost;
+
+            for (next, step) in successors(state) {
+                if visited.contains(&next) {
+                    continue;
+                }
+                let next_cost = cost + step;
+                candidates.push((next_cost + heuristic(&next), next_cost, next, index));
+            }
+        }
+        candidates.sort_by_key(|(rank, cost, _, _)| (*rank, *cost));
+
+        beam.clear();
+        for (_, cost, state, predecessor) in candidates {
+            if beam.len() == width {
+                break;
+            }
+            // The same state may be a candidate multiple times, only keep the best one.
+            if visited.insert(state.clone()) {
+                beam.push(states.len());
+                states.push((state, cost, Some(predecessor)));
+            }
+        }
+
+        if beam.is_empty() {
+            return None;
+        }
+    };
+
+    let cost = states[goal].1;
+    let mut path = vec![goal];
+    while let Some(previous) = states[*path.last().unwrap()].2 {
+        path.push(previous);
+    }
+
+    Some(Path {
+        cost,
+        states: path
+            .into_iter()
+            .rev()
+            .map(|index| states[index].0.clone())
+            .collect(),
+    })
+}
+
+/// Finds the shortest path from `start` to a state for which `is_goal` returns true, using depth first
+/// searches limited to an increasing depth, up to `max_depth` steps.
+///
+/// Needs far less memory than [`bfs_to`], as only the current path is stored, at the cost of
+/// exploring states again for every depth. States are never repeated within a path.
+pub fn iddfs<S, I>(
+    start: S,
+    max_depth: usize,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+{
+    if is_goal(&start) {
+        return Some(Path {
+            cost: 0,
+            states: vec![start],
+        });
+    }
+
+    for depth in 1..=max_depth {
+        let mut path = vec![start.clone()];
+        let mut on_path: HashSet<S> = HashSet::from([start.clone()]);
+        let mut stack = vec![successors(&start).into_iter()];
+        // Whether any state was cut off by the depth limit, otherwise searching deeper is pointless.
+        let mut cut_off = false;
+
+        while let Some(next) = stack.last_mut().map(|children| children.next()) {
+            let Some(next) = next else {
+                stack.pop();
+                on_path.remove(&path.pop().unwrap());
+                continue;
+            };
+            if on_path.contains(&next) {
+                continue;
+            }
+
+            if is_goal(&next) {
+                path.push(next);
+                return Some(Path {
+                    cost: path.len() - 1,
+                    states: path,
+                });
+            }
+
+            if path.len() < depth {
+                stack.push(successors(&next).into_iter());
+                on_path.insert(next.clone());
+                path.push(next);
+            } else {
+                cut_off = true;
+            }
+        }
+
+        if !cut_off {
+            return None;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A directed graph of letters, with the cost of every edge.
+    fn graph(edges: &[(char, char, u64)]) -> impl FnMut(&char) -> Vec<(char, u64)> {
+        let edges = edges.to_vec();
+        move |state| {
+            edges
+                .iter()
+                .filter(|(from, _, _)| from == state)
+                .map(|&(_, to, cost)| (to, cost))
+                .collect()
+        }
+    }
+
+    /// Steps of +1 and *2 on numbers, limited to `limit`.
+    fn arithmetic(limit: u32) -> impl FnMut(&u32) -> Vec<u32> {
+        move |&n| [n + 1, n * 2].into_iter().filter(|&v| v <= limit).collect()
+    }
+
+    #[test]
+    fn stopped_search_only_keeps_expanded_states() {
+        // When the goal d is taken from the queue, c is still queued with the cost 10 instead of 3.
+        let edges = graph(&[('a', 'b', 1), ('a', 'c', 10), ('b', 'd', 1), ('d', 'c', 1)]);
+        let (paths, goal) = weighted_search(['a'], edges, |&state| state == 'd', |_| 0);
+
+        assert_eq!(goal, Some('d'));
+        assert_eq!(paths.cost(&'c'), None);
+        assert_eq!(paths.predecessor(&'c'), None);
+        assert_eq!(paths.cost(&'b'), Some(1));
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths.path_to(&'d').unwrap().states, vec!['a', 'b', 'd']);
+    }
+
+    #[test]
+    fn bfs_finds_shortest_paths() {
+        let paths = bfs([1], arithmetic(10));
+        assert_eq!(paths.len(), 10);
+        assert_eq!(paths.cost(&1), Some(0));
+        assert_eq!(paths.cost(&8), Some(3));
+        assert_eq!(paths.cost(&10), Some(4));
+        assert_eq!(paths.path_to(&8).unwrap().states, vec![1, 2, 4, 8]);
+        assert_eq!(paths.predecessor(&1), None);
+        assert_eq!(paths.cost(&11), None);
+
+        let path = bfs_to([1], arithmetic(100), |&n| n == 24).unwrap();
+        assert_eq!(path.states, vec![1, 2, 3, 6, 12, 24]);
+        assert_eq!((path.cost, path.steps()), (5, 5));
+        assert_eq!((*path.start(), *path.goal()), (1, 24));
+        assert_eq!(bfs_to([1], arithmetic(10), |&n| n == 11), None);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheap_detours() {
+        let edges = [
+            ('a', 'b', 5),
+            ('a', 'c', 1),
+            ('c', 'd', 1),
+            ('d', 'b', 1),
+            ('b', 'e', 1),
+        ];
+        let paths = dijkstra(['a'], graph(&edges));
+        assert_eq!(paths.cost(&'b'), Some(3));
+        assert_eq!(paths.cost(&'e'), Some(4));
+
+        let path = dijkstra_to(['a'], graph(&edges), |&v| v == 'e').unwrap();
+        assert_eq!(path.states, vec!['a', 'c', 'd', 'b', 'e']);
+        assert_eq!(path.cost, 4);
+        assert_eq!(dijkstra_to(['e'], graph(&edges), |&v| v == 'a'), None);
+    }
+
+    #[test]
+    fn a_star_reopens_states_with_inconsistent_heuristic() {
+        // The heuristic overrates 'a', so 'c' is first expanded through the more expensive 'b'.
+        let edges = [
+            ('s', 'a', 1),
+            ('s', 'b', 2),
+            ('a', 'c', 1),
+            ('b', 'c', 2),
+            ('c', 'g', 3),
+        ];
+        let heuristic = |state: &char| if *state == 'a' { 4 } else { 0 };
+
+        let path = a_star(['s'], graph(&edges), |&v| v == 'g', heuristic).unwrap();
+        assert_eq!(path.states, vec!['s', 'a', 'c', 'g']);
+        assert_eq!(path.cost, 5);
+    }
+
+    #[test]
+    fn a_star_on_a_line() {
+        let path = a_star(
+            [0_i32],
+            |&n| [(n - 1, 1), (n + 1, 1)],
+            |&n| n == 5,
+            |&n| n.abs_diff(5) as u64,
+        )
+        .unwrap();
+        assert_eq!(path.states, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn bidirectional_bfs_finds_shortest_path() {
+        let steps = |&n: &i32| [n - 1, n + 1, n * 3];
+        let path = bidirectional_bfs(1, 10, steps, |&n: &i32| {
+            let mut previous = vec![n + 1, n - 1];
+            if n % 3 == 0 {
+                previous.push(n / 3);
+            }
+            previous
+        })
+        .unwrap();
+        // 1 -> 3 -> 9 -> 10
+        assert_eq!(path.cost, 3);
+        assert_eq!(path.states, vec![1, 3, 9, 10]);
+
+        let same = bidirectional_bfs(4, 4, steps, steps).unwrap();
+        assert_eq!((same.cost, same.states), (0, vec![4]));
+        assert_eq!(bidirectional_bfs(1, 5, |_| [], |_| []), None);
+    }
+
+    #[test]
+    fn beam_search_keeps_only_the_best_states() {
+        let edges = [('s', 'a', 1), ('s', 'b', 5), ('a', 'g', 10), ('b', 'g', 1)];
+        // With a width of one, the expensive 'b' is dropped in favour of 'a'.
+        let narrow = beam_search('s', 1, graph(&edges), |&v| v == 'g', |_| 0).unwrap();
+        assert_eq!((narrow.cost, narrow.states), (11, vec!['s', 'a', 'g']));
+
+        let wide = beam_search('s', 2, graph(&edges), |&v| v == 'g', |_| 0).unwrap();
+        assert_eq!((wide.cost, wide.states), (6, vec!['s', 'b', 'g']));
+        assert_eq!(
+            beam_search('s', 2, graph(&edges), |&v| v == 'x', |_| 0),
+            None
+        );
+    }
+
+    #[test]
+    fn iddfs_finds_shortest_path_within_depth() {
+        let path = iddfs(1, 10, arithmetic(100), |&n| n == 24).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.states.len(), 6);
+        assert_eq!(iddfs(1, 4, arithmetic(100), |&n| n == 24), None);
+        assert_eq!(iddfs(3, 0, arithmetic(100), |&n| n == 3).unwrap().cost, 0);
+        // The whole state space is exhausted before reaching the maximum depth.
+        assert_eq!(iddfs(1, 100, arithmetic(3), |&n| n == 5), None);
+    }
+}
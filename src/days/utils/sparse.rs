@@ -0,0 +1,379 @@
+use super::{BitGrid, Direction, Map, Point2};
+use crate::days::Answer;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A 2-Dimensional grid storing only occupied cells, which may have negative coordinates.
+///
+/// The bounding box grows with every inserted cell, but doesn't shrink when cells are removed,
+/// so it keeps covering the whole area of a grid that is only cleared over time.
+/// Use [`Self::tight_bounds`] for the box around the currently occupied cells.
+#[derive(Debug, Clone, Default)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2<isize>, T>,
+    bounds: Option<(Point2<isize>, Point2<isize>)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Parses a multi-line &str, with the first char at (0, 0).
+    ///
+    /// `f` converts chars to cells, returning None for empty positions.
+    /// Empty positions are still included in the bounding box.
+    pub fn from_str_with(value: &str, mut f: impl FnMut(char) -> Option<T>) -> Self {
+        let mut grid = Self::new();
+
+        for (y, line) in value.lines().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                let point = Point2::new(x as isize, y as isize);
+                grid.include(point);
+                if let Some(cell) = f(char) {
+                    grid.insert(point, cell);
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Extends the bounding box to include the point, without occupying it.
+    pub fn include(&mut self, point: Point2<isize>) {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                Point2::new(min.x.min(point.x), min.y.min(point.y)),
+                Point2::new(max.x.max(point.x), max.y.max(point.y)),
+            ),
+            None => (point, point),
+        });
+    }
+
+    /// Sets a cell, returning its previous value.
+    pub fn insert(&mut self, point: Point2<isize>, value: T) -> Option<T> {
+        self.include(point);
+        self.cells.insert(point, value)
+    }
+
+    /// Clears a cell, returning its previous value.
+    pub fn remove(&mut self, point: Point2<isize>) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    pub fn get(&self, point: Point2<isize>) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point2<isize>) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Point2<isize>) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// The number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The number of occupied cells matching the predicate.
+    pub fn count(&self, mut predicate: impl FnMut(&T) -> bool) -> usize {
+        self.cells.values().filter(|v| predicate(v)).count()
+    }
+
+    /// Iterates over the occupied cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<isize>, &T)> {
+        self.cells.iter().map(|(point, v)| (*point, v))
+    }
+
+    /// Iterates over the points of the occupied cells, in no particular order.
+    pub fn positions(&self) -> impl Iterator<Item = Point2<isize>> {
+        self.cells.keys().copied()
+    }
+
+    /// The minimum and maximum corner (both inclusive) of every point ever inserted or included.
+    pub fn bounds(&self) -> Option<(Point2<isize>, Point2<isize>)> {
+        self.bounds
+    }
+
+    /// The minimum and maximum corner (both inclusive) of the currently occupied cells.
+    pub fn tight_bounds(&self) -> Option<(Point2<isize>, Point2<isize>)> {
+        let mut points = self.positions();
+        let first = points.next()?;
+
+        Some(points.fold((first, first), |(min, max), point| {
+            (
+                Point2::new(min.x.min(point.x), min.y.min(point.y)),
+                Point2::new(max.x.max(point.x), max.y.max(point.y)),
+            )
+        }))
+    }
+
+    /// The width and height of the bounding box.
+    pub fn dimensions(&self) -> (usize, usize) {
+        match self.bounds {
+            Some((min, max)) => ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize),
+            None => (0, 0),
+        }
+    }
+
+    fn neighbours_in(
+        &self,
+        point: Point2<isize>,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = (Point2<isize>, &T)> {
+        directions.iter().filter_map(move |direction| {
+            let neighbour = point + *direction;
+            Some((neighbour, self.cells.get(&neighbour)?))
+        })
+    }
+
+    /// Iterates over the occupied, orthogonally adjacent cells.
+    pub fn neighbours4(&self, point: Point2<isize>) -> impl Iterator<Item = (Point2<isize>, &T)> {
+        self.neighbours_in(point, &Direction::ALL)
+    }
+
+    /// Iterates over the occupied, orthogonally and diagonally adjacent cells.
+    pub fn neighbours8(&self, point: Point2<isize>) -> impl Iterator<Item = (Point2<isize>, &T)> {
+        self.neighbours_in(point, &Direction::ALL_WITH_DIAGONALS)
+    }
+
+    /// Renders the bounding box as text, converting every position using `f`.
+    pub fn render(&self, mut f: impl FnMut(Option<&T>) -> char) -> String {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| f(self.get(Point2::new(x, y))))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders the bounding box as an [`Answer::Bitmap`], with pixels set where `f` returns true.
+    pub fn to_bitmap(&self, mut f: impl FnMut(&T) -> bool) -> Answer {
+        let Some((min, max)) = self.bounds else {
+            return Answer::Bitmap(Vec::new());
+        };
+
+        Answer::Bitmap(
+            (min.y..=max.y)
+                .map(|y| {
+                    (min.x..=max.x)
+                        .map(|x| self.get(Point2::new(x, y)).is_some_and(&mut f))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Converts the occupied tiles of a dense [`Map`], keeping the tiles for which `keep` returns true.
+    ///
+    /// The bounding box covers the whole map.
+    pub fn from_map(map: &Map<T>, mut keep: impl FnMut(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        let mut grid = Self::new();
+        let (width, height) = map.dimensions();
+        if width != 0 && height != 0 {
+            grid.include(Point2::new(0, 0));
+            grid.include(Point2::new(width as isize - 1, height as isize - 1));
+        }
+
+        for ((x, y), tile) in map.iter() {
+            if keep(tile) {
+                grid.insert(Point2::new(x as isize, y as isize), tile.clone());
+            }
+        }
+
+        grid
+    }
+
+    /// Converts the bounding box to a dense [`Map`], filling empty positions with `empty`.
+    ///
+    /// The minimum corner of the bounding box becomes (0, 0) of the map.
+    pub fn to_map(&self, empty: T) -> Map<T>
+    where
+        T: Clone,
+    {
+        let (width, height) = self.dimensions();
+        let mut map = Map::filled(width, height, empty);
+
+        if let Some((min, _)) = self.bounds {
+            for (point, v) in self.iter() {
+                map.set(
+                    (point.x - min.x) as usize,
+                    (point.y - min.y) as usize,
+                    v.clone(),
+                );
+            }
+        }
+
+        map
+    }
+}
+
+impl SparseGrid<()> {
+    /// Creates a grid with a cell for every set bit of a [`BitGrid`].
+    ///
+    /// The bounding box covers the whole BitGrid, including empty borders.
+    pub fn from_bitgrid(grid: &BitGrid) -> Self {
+        let mut sparse = Self::new();
+        let (width, height) = grid.dimensions();
+        if width != 0 && height != 0 {
+            sparse.include(Point2::new(0, 0));
+            sparse.include(Point2::new(width as isize - 1, height as isize - 1));
+        }
+
+        for (x, y) in grid.iter_ones() {
+            sparse.insert(Point2::new(x as isize, y as isize), ());
+        }
+
+        sparse
+    }
+}
+
+/// Compares the occupied cells, ignoring the bounding box, like the [`Hash`] implementation.
+impl<T> PartialEq for SparseGrid<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<T> Eq for SparseGrid<T> where T: Eq {}
+
+/// Hashes the occupied cells, ignoring the bounding box.
+impl<T> Hash for SparseGrid<T>
+where
+    T: Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let mut cells: Vec<(&Point2<isize>, &T)> = self.cells.iter().collect();
+        cells.sort_by_key(|(point, _)| **point);
+        cells.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasher, RandomState};
+
+    fn grid() -> SparseGrid<char> {
+        SparseGrid::from_str_with("#..\n.##\n...", |c| (c == '#').then_some(c))
+    }
+
+    #[test]
+    fn parsing_includes_empty_positions_in_bounds() {
+        let grid = grid();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.dimensions(), (3, 3));
+        assert_eq!(grid.bounds(), Some((Point2::new(0, 0), Point2::new(2, 2))));
+        assert_eq!(
+            grid.tight_bounds(),
+            Some((Point2::new(0, 0), Point2::new(2, 1)))
+        );
+        assert_eq!(grid.render(|v| *v.unwrap_or(&'.')), "#..\n.##\n...");
+        assert_eq!(SparseGrid::<char>::new().render(|_| '#'), "");
+        assert_eq!(SparseGrid::<char>::new().dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn bounds_grow_but_never_shrink() {
+        let mut grid = grid();
+        assert_eq!(grid.insert(Point2::new(-2, 1), 'x'), None);
+        assert_eq!(grid.insert(Point2::new(-2, 1), 'y'), Some('x'));
+        assert_eq!(grid.dimensions(), (5, 3));
+
+        assert_eq!(grid.remove(Point2::new(-2, 1)), Some('y'));
+        assert_eq!(grid.remove(Point2::new(-2, 1)), None);
+        assert_eq!(grid.dimensions(), (5, 3));
+        assert_eq!(
+            grid.tight_bounds(),
+            Some((Point2::new(0, 0), Point2::new(2, 1)))
+        );
+        assert!(!grid.contains(Point2::new(-2, 1)));
+    }
+
+    #[test]
+    fn neighbours_only_include_occupied_cells() {
+        let grid = grid();
+        let center = Point2::new(1, 1);
+        assert_eq!(
+            grid.neighbours4(center).collect::<Vec<_>>(),
+            vec![(Point2::new(2, 1), &'#')]
+        );
+        assert_eq!(grid.neighbours8(center).count(), 2);
+        assert_eq!(grid.neighbours8(Point2::new(-1, -1)).count(), 1);
+        assert_eq!(grid.count(|&c| c == '#'), 3);
+    }
+
+    #[test]
+    fn conversions_keep_positions_and_bounds() {
+        let mut grid = grid();
+        grid.insert(Point2::new(-1, 0), '#');
+
+        let map = grid.to_map('.');
+        assert_eq!(map.dimensions(), (4, 3));
+        assert_eq!(map.to_string(), "##..\n..##\n....");
+
+        let back = SparseGrid::from_map(&map, |&c| c == '#');
+        assert_eq!(back.len(), 4);
+        assert_eq!(back.dimensions(), (4, 3));
+
+        assert_eq!(
+            grid.to_bitmap(|_| true),
+            Answer::Bitmap(vec![
+                vec![true, true, false, false],
+                vec![false, false, true, true],
+                vec![false, false, false, false],
+            ])
+        );
+    }
+
+    #[test]
+    fn from_bitgrid_covers_the_whole_grid() {
+        let bits = BitGrid::from_str_with("...\n.#.\n...", |c| c == '#');
+        let grid = SparseGrid::from_bitgrid(&bits);
+        assert_eq!(
+            grid.positions().collect::<Vec<_>>(),
+            vec![Point2::new(1, 1)]
+        );
+        assert_eq!(grid.dimensions(), (3, 3));
+
+        assert!(
+            SparseGrid::from_bitgrid(&BitGrid::new(0, 0))
+                .bounds()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn hash_ignores_bounds() {
+        let mut a = grid();
+        let b = grid();
+        a.include(Point2::new(10, 10));
+        let hasher = RandomState::new();
+        assert_eq!(hasher.hash_one(&a), hasher.hash_one(&b));
+
+        a.insert(Point2::new(10, 10), '#');
+        assert_ne!(hasher.hash_one(&a), hasher.hash_one(&b));
+    }
+}
@@ -0,0 +1,355 @@
+//! Transformations of a [`Map`], and views into parts of it which don't copy any tiles.
+
+use super::Map;
+
+impl<T> Map<T> {
+    /// Creates a Map from a multi-line &str whose lines may have different lengths,
+    /// filling the end of shorter lines with `padding`.
+    pub fn from_str_padded(value: &str, padding: T, mut f: impl FnMut(char) -> T) -> Self
+    where
+        T: Clone,
+    {
+        let width = value.lines().map(|v| v.chars().count()).max().unwrap_or(0);
+        let tiles: Vec<Vec<T>> = value
+            .lines()
+            .map(|line| {
+                let mut tiles: Vec<T> = line.chars().map(&mut f).collect();
+                tiles.resize(width, padding.clone());
+                tiles
+            })
+            .collect();
+
+        Self {
+            dim: (width, tiles.len()),
+            tiles,
+        }
+    }
+
+    /// Creates a Map of the specified dimensions, getting every tile from `f(x, y)`.
+    fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        Self {
+            tiles: (0..height)
+                .map(|y| (0..width).map(|x| f(x, y)).collect())
+                .collect(),
+            dim: (width, height),
+        }
+    }
+
+    /// Swaps the x and y axis, so lines become columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = self.dim;
+        Self::from_fn(height, width, |x, y| self.tiles[x][y].clone())
+    }
+
+    /// Rotates the map by 90° clockwise.
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = self.dim;
+        Self::from_fn(height, width, |x, y| self.tiles[height - 1 - x][y].clone())
+    }
+
+    /// Rotates the map by 90° counterclockwise.
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = self.dim;
+        Self::from_fn(height, width, |x, y| self.tiles[x][width - 1 - y].clone())
+    }
+
+    /// Rotates the map by 180°.
+    pub fn rotate_180(&self) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = self.dim;
+        Self::from_fn(width, height, |x, y| {
+            self.tiles[height - 1 - y][width - 1 - x].clone()
+        })
+    }
+
+    /// Mirrors the map, so the first column becomes the last.
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = self.dim;
+        Self::from_fn(width, height, |x, y| self.tiles[y][width - 1 - x].clone())
+    }
+
+    /// Mirrors the map, so the first line becomes the last.
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = self.dim;
+        Self::from_fn(width, height, |x, y| self.tiles[height - 1 - y][x].clone())
+    }
+
+    /// A view of the whole map.
+    pub fn as_view(&self) -> MapView<'_, T> {
+        MapView {
+            map: self,
+            offset: (0, 0),
+            dim: self.dim,
+        }
+    }
+
+    /// A view of the `width` by `height` tiles starting at (x, y).
+    ///
+    /// Panics if the area isn't completely within the map.
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> MapView<'_, T> {
+        self.as_view().view(x, y, width, height)
+    }
+
+    /// Iterates over the tiles of a line.
+    ///
+    /// Panics if the line is outside the map.
+    pub fn row(&self, y: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.tiles[y].iter()
+    }
+
+    /// Iterates over the tiles of a column, from top to bottom.
+    ///
+    /// Panics if the column is outside the map.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.dim.0, "Column outside of the map.");
+        self.tiles.iter().map(move |line| &line[x])
+    }
+
+    /// Iterates over all lines, each as a view.
+    pub fn rows(&self) -> impl Iterator<Item = MapView<'_, T>> {
+        self.as_view().rows()
+    }
+
+    /// Iterates over all columns, each as a view.
+    pub fn columns(&self) -> impl Iterator<Item = MapView<'_, T>> {
+        self.as_view().columns()
+    }
+
+    /// Iterates over every `width` by `height` area of the map, line by line.
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = MapView<'_, T>> {
+        self.as_view().windows(width, height)
+    }
+}
+
+/// A rectangular part of a [`Map`], borrowing its tiles.
+#[derive(Debug)]
+pub struct MapView<'a, T> {
+    map: &'a Map<T>,
+    offset: (usize, usize),
+    dim: (usize, usize),
+}
+
+impl<T> Clone for MapView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MapView<'_, T> {}
+
+impl<'a, T> MapView<'a, T> {
+    /// Returns the dimensions of the view as a tuple `(x: usize, y: usize)`
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    /// The position of the top left tile of the view within the map.
+    pub fn offset(&self) -> (usize, usize) {
+        self.offset
+    }
+
+    /// Gets a tile, relative to the top left of the view.
+    ///
+    /// May return None, if the position is outside the view.
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x >= self.dim.0 || y >= self.dim.1 {
+            return None;
+        }
+        self.map.get(self.offset.0 + x, self.offset.1 + y)
+    }
+
+    /// A view of the `width` by `height` tiles starting at (x, y), relative to this view.
+    ///
+    /// Panics if the area isn't completely within this view.
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> MapView<'a, T> {
+        assert!(
+            x + width <= self.dim.0 && y + height <= self.dim.1,
+            "View outside of the map."
+        );
+
+        MapView {
+            map: self.map,
+            offset: (self.offset.0 + x, self.offset.1 + y),
+            dim: (width, height),
+        }
+    }
+
+    /// Iterates over the tiles of a line of the view.
+    ///
+    /// Panics if the line is outside the view.
+    pub fn row(&self, y: usize) -> impl DoubleEndedIterator<Item = &'a T> + use<'a, T> {
+        assert!(y < self.dim.1, "Row outside of the view.");
+        let (start, end) = (self.offset.0, self.offset.0 + self.dim.0);
+        self.map.tiles[self.offset.1 + y][start..end].iter()
+    }
+
+    /// Iterates over the tiles of a column of the view, from top to bottom.
+    ///
+    /// Panics if the column is outside the view.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &'a T> + use<'a, T> {
+        assert!(x < self.dim.0, "Column outside of the view.");
+        let x = self.offset.0 + x;
+        let (start, end) = (self.offset.1, self.offset.1 + self.dim.1);
+        self.map.tiles[start..end].iter().map(move |line| &line[x])
+    }
+
+    /// Iterates over all lines, each as a view.
+    pub fn rows(&self) -> impl Iterator<Item = MapView<'a, T>> + use<'a, T> {
+        let view = *self;
+        (0..self.dim.1).map(move |y| view.view(0, y, view.dim.0, 1))
+    }
+
+    /// Iterates over all columns, each as a view.
+    pub fn columns(&self) -> impl Iterator<Item = MapView<'a, T>> + use<'a, T> {
+        let view = *self;
+        (0..self.dim.0).map(move |x| view.view(x, 0, 1, view.dim.1))
+    }
+
+    /// Iterates over every `width` by `height` area of the view, line by line.
+    pub fn windows(
+        &self,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = MapView<'a, T>> + use<'a, T> {
+        let view = *self;
+        let count_x = (self.dim.0 + 1).saturating_sub(width);
+        let count_y = (self.dim.1 + 1).saturating_sub(height);
+
+        (0..count_y).flat_map(move |y| (0..count_x).map(move |x| view.view(x, y, width, height)))
+    }
+
+    /// Iterates over all tiles of the view together with their position relative to the view, line by line.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &'a T)> + use<'a, T> {
+        let view = *self;
+        (0..self.dim.1).flat_map(move |y| view.row(y).enumerate().map(move |(x, v)| ((x, y), v)))
+    }
+
+    /// Copies the tiles of the view into a new map.
+    pub fn to_map(self) -> Map<T>
+    where
+        T: Clone,
+    {
+        Map::from_fn(self.dim.0, self.dim.1, |x, y| {
+            self.get(x, y).unwrap().clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> Map<char> {
+        Map::from_str_with("abc\ndef", |c| c)
+    }
+
+    #[test]
+    fn transformations() {
+        let map = map();
+        assert_eq!(map.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(map.rotate_right().to_string(), "da\neb\nfc");
+        assert_eq!(map.rotate_left().to_string(), "cf\nbe\nad");
+        assert_eq!(map.rotate_180().to_string(), "fed\ncba");
+        assert_eq!(map.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(map.flip_vertical().to_string(), "def\nabc");
+
+        assert_eq!(map.rotate_right().rotate_left(), map);
+        assert_eq!(map.rotate_right().rotate_right(), map.rotate_180());
+        assert_eq!(map.transpose().dimensions(), (2, 3));
+    }
+
+    #[test]
+    fn padded_lines() {
+        let map = Map::from_str_padded("ab\nc\n", ' ', |c| c);
+        assert_eq!(map.dimensions(), (2, 2));
+        assert_eq!(map.to_string(), "ab\nc ");
+        assert_eq!(Map::from_str_padded("", ' ', |c| c).dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let map = map();
+        assert_eq!(map.row(1).collect::<String>(), "def");
+        assert_eq!(map.column(2).collect::<String>(), "cf");
+        assert_eq!(map.column(0).rev().collect::<String>(), "da");
+        assert_eq!(
+            map.rows()
+                .map(|v| v.to_map().to_string())
+                .collect::<Vec<_>>(),
+            vec!["abc", "def"]
+        );
+        assert_eq!(map.columns().count(), 3);
+    }
+
+    #[test]
+    fn views_are_relative_to_their_offset() {
+        let map = Map::from_str_with("abcd\nefgh\nijkl", |c| c);
+        let view = map.view(1, 1, 3, 2);
+        assert_eq!(view.dimensions(), (3, 2));
+        assert_eq!(view.offset(), (1, 1));
+        assert_eq!(view.get(0, 0), Some(&'f'));
+        assert_eq!(view.get(3, 0), None);
+        assert_eq!(view.get(0, 2), None);
+        assert_eq!(view.row(1).collect::<String>(), "jkl");
+        assert_eq!(view.column(2).collect::<String>(), "hl");
+        assert_eq!(view.iter().next(), Some(((0, 0), &'f')));
+        assert_eq!(view.to_map().to_string(), "fgh\njkl");
+
+        let inner = view.view(1, 0, 2, 2);
+        assert_eq!(inner.offset(), (2, 1));
+        assert_eq!(inner.to_map().to_string(), "gh\nkl");
+    }
+
+    #[test]
+    fn windows() {
+        let map = Map::from_str_with("abc\ndef\nghi", |c| c);
+        let windows: Vec<String> = map.windows(2, 2).map(|v| v.to_map().to_string()).collect();
+        assert_eq!(windows, vec!["ab\nde", "bc\nef", "de\ngh", "ef\nhi"]);
+        assert_eq!(map.windows(3, 3).count(), 1);
+        assert_eq!(map.windows(4, 1).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "View outside of the map.")]
+    fn view_outside_panics() {
+        map().view(2, 0, 2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Row outside of the view.")]
+    fn row_outside_view_panics() {
+        // The line exists in the map, but not in the view.
+        let map = map();
+        let _ = map.view(0, 0, 3, 1).row(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Column outside of the view.")]
+    fn column_outside_view_panics() {
+        let map = map();
+        let _ = map.view(0, 0, 2, 2).column(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Column outside of the map.")]
+    fn column_outside_map_panics() {
+        let _ = map().column(3);
+    }
+}
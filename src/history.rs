@@ -0,0 +1,482 @@
+use crate::{Part, RunTimings, dynamic_range_time_format};
+use colored::*;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const HISTORY_PATH: &str = "./.aoc25_history.tsv";
+const HISTORY_HEADER: &str =
+    "timestamp\tcommit\tmachine\tprofile\tday\tpart\tinput_hash\tinit_ns\tone_ns\ttwo_ns";
+
+/// A single timed run, as stored in the history file.
+#[derive(Debug, Clone)]
+struct RunRecord {
+    timestamp: u64,
+    commit: String,
+    machine: String,
+    profile: String,
+    day: u8,
+    part: String,
+    input_hash: String,
+    init: Duration,
+    one: Option<Duration>,
+    two: Option<Duration>,
+}
+
+impl RunRecord {
+    fn to_line(&self) -> String {
+        let format_duration = |d: Option<Duration>| match d {
+            Some(d) => d.as_nanos().to_string(),
+            None => "-".to_owned(),
+        };
+
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.timestamp,
+            self.commit,
+            self.machine,
+            self.profile,
+            self.day,
+            self.part,
+            self.input_hash,
+            self.init.as_nanos(),
+            format_duration(self.one),
+            format_duration(self.two)
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 10 {
+            return None;
+        }
+
+        let parse_duration = |v: &str| -> Option<Option<Duration>> {
+            match v {
+                "-" => Some(None),
+                v => Some(Some(Duration::from_nanos(v.parse().ok()?))),
+            }
+        };
+
+        Some(Self {
+            timestamp: fields[0].parse().ok()?,
+            commit: fields[1].to_owned(),
+            machine: fields[2].to_owned(),
+            profile: fields[3].to_owned(),
+            day: fields[4].parse().ok()?,
+            part: fields[5].to_owned(),
+            input_hash: fields[6].to_owned(),
+            init: parse_duration(fields[7])??,
+            one: parse_duration(fields[8])?,
+            two: parse_duration(fields[9])?,
+        })
+    }
+
+    /// Whether two records were measured under comparable conditions.
+    fn is_comparable(&self, other: &Self) -> bool {
+        self.day == other.day
+            && self.input_hash == other.input_hash
+            && self.machine == other.machine
+            && self.profile == other.profile
+    }
+}
+
+/// Hashes the input using 64-bit FNV-1a, which, unlike the std hasher, is stable between builds.
+fn hash_input(input: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.trim_end().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Returns the short hash of the checked out commit, marked with `+dirty` if there are uncommitted changes.
+fn current_commit() -> String {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned());
+
+    let Some(commit) = commit else {
+        return "unknown".to_owned();
+    };
+
+    let dirty = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .map(|output| !output.stdout.is_empty())
+        .unwrap_or(false);
+
+    if dirty { commit + "+dirty" } else { commit }
+}
+
+fn machine_id() -> String {
+    env_or_file("HOSTNAME", "/etc/hostname")
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|v| v.trim().replace('\t', " "))
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "unknown".to_owned())
+}
+
+fn env_or_file(variable: &str, path: &str) -> Option<String> {
+    std::env::var(variable)
+        .ok()
+        .or_else(|| fs::read_to_string(path).ok())
+}
+
+fn build_profile() -> &'static str {
+    if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    }
+}
+
+fn load_history() -> Vec<RunRecord> {
+    match fs::read_to_string(Path::new(HISTORY_PATH)) {
+        Ok(content) => content
+            .lines()
+            .skip(1)
+            .filter_map(RunRecord::from_line)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Appends a timed run to the local history file.
+pub fn record_history(
+    day: u8,
+    part: &Part,
+    input: &str,
+    timings: &RunTimings,
+) -> std::io::Result<()> {
+    let record = RunRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|v| v.as_secs())
+            .unwrap_or(0),
+        commit: current_commit(),
+        machine: machine_id(),
+        profile: build_profile().to_owned(),
+        day,
+        part: match part {
+            Part::One => "1",
+            Part::Two => "2",
+            Part::Both => "b",
+        }
+        .to_owned(),
+        input_hash: hash_input(input),
+        init: timings.init,
+        one: timings.one,
+        two: timings.two,
+    };
+
+    let path = Path::new(HISTORY_PATH);
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{}", HISTORY_HEADER)?;
+    }
+    writeln!(file, "{}", record.to_line())
+}
+
+/// Mean and sample variance of a list of durations, in nanoseconds.
+fn mean_and_variance(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    if samples.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance)
+}
+
+/// One-sided critical values of Student's t-distribution at 95% confidence.
+fn t_critical(degrees_of_freedom: f64) -> f64 {
+    const TABLE: [f64; 30] = [
+        6.314, 2.920, 2.353, 2.132, 2.015, 1.943, 1.895, 1.860, 1.833, 1.812, 1.796, 1.782, 1.771,
+        1.761, 1.753, 1.746, 1.740, 1.734, 1.729, 1.725, 1.721, 1.717, 1.714, 1.711, 1.708, 1.706,
+        1.703, 1.701, 1.699, 1.697,
+    ];
+
+    let df = degrees_of_freedom.floor().max(1.0) as usize;
+    TABLE.get(df - 1).copied().unwrap_or(1.645)
+}
+
+type PhaseGetter = fn(&RunRecord) -> Option<Duration>;
+
+#[derive(Debug, PartialEq)]
+enum Verdict {
+    Faster,
+    Unchanged,
+    /// Slower by more than the threshold, but there are too few samples for a t-test.
+    PossiblySlower,
+    Slower,
+}
+
+/// Compares two sets of samples using Welch's t-test.
+///
+/// A slowdown is only flagged if it is larger than `threshold` (relative to the baseline mean)
+/// and statistically significant.
+fn compare_samples(baseline: &[f64], latest: &[f64], threshold: f64) -> (f64, Verdict) {
+    let (b_mean, _) = mean_and_variance(baseline);
+    let (l_mean, _) = mean_and_variance(latest);
+    let change = if b_mean > 0.0 {
+        (l_mean - b_mean) / b_mean
+    } else {
+        0.0
+    };
+
+    if change <= -threshold {
+        return (change, Verdict::Faster);
+    }
+    if change < threshold {
+        return (change, Verdict::Unchanged);
+    }
+    if baseline.len() < 2 || latest.len() < 2 {
+        return (change, Verdict::PossiblySlower);
+    }
+
+    let Some((t, df)) = welch_t_test(baseline, latest) else {
+        return (change, Verdict::Slower);
+    };
+
+    if t > t_critical(df) {
+        (change, Verdict::Slower)
+    } else {
+        (change, Verdict::Unchanged)
+    }
+}
+
+/// Welch's t statistic of `latest` against `baseline`, and its degrees of freedom.
+///
+/// Returns None if both sets of samples have no variance, so the statistic is undefined.
+fn welch_t_test(baseline: &[f64], latest: &[f64]) -> Option<(f64, f64)> {
+    let (b_mean, b_var) = mean_and_variance(baseline);
+    let (l_mean, l_var) = mean_and_variance(latest);
+    let (b_n, l_n) = (baseline.len() as f64, latest.len() as f64);
+
+    let standard_error = (b_var / b_n + l_var / l_n).sqrt();
+    if standard_error == 0.0 {
+        return None;
+    }
+
+    let t = (l_mean - b_mean) / standard_error;
+    let df = (b_var / b_n + l_var / l_n).powi(2)
+        / ((b_var / b_n).powi(2) / (b_n - 1.0) + (l_var / l_n).powi(2) / (l_n - 1.0));
+
+    Some((t, df))
+}
+
+/// Compares the latest recorded runs of a day to a baseline commit, or to the previous runs.
+///
+/// Only runs with the same input, machine and build profile as the latest run are considered.
+/// If no baseline is given, the runs of the most recent other commit are used,
+/// or all earlier runs if every run was recorded on the same commit.
+///
+/// Returns false if a significant slowdown was found.
+pub fn compare_history(day: u8, baseline: Option<&str>, threshold_percent: f64) -> bool {
+    println!("{} Day {}", "Comparing".green().bold(), day);
+    println!("{}", "-----------------------".green().bold());
+
+    let history: Vec<RunRecord> = load_history()
+        .into_iter()
+        .filter(|v| v.day == day)
+        .collect();
+    let Some(latest) = history.last() else {
+        println!("No recorded runs for day {}.", day);
+        return true;
+    };
+
+    let comparable: Vec<&RunRecord> = history.iter().filter(|v| v.is_comparable(latest)).collect();
+    let latest_runs: Vec<&RunRecord> = comparable
+        .iter()
+        .filter(|v| v.commit == latest.commit)
+        .copied()
+        .collect();
+
+    let baseline_runs: Vec<&RunRecord> = match baseline {
+        Some(baseline) => comparable
+            .iter()
+            .filter(|v| v.commit.starts_with(baseline) && v.commit != latest.commit)
+            .copied()
+            .collect(),
+        None => match comparable.iter().rev().find(|v| v.commit != latest.commit) {
+            Some(previous) => comparable
+                .iter()
+                .filter(|v| v.commit == previous.commit)
+                .copied()
+                .collect(),
+            None => comparable[..comparable.len() - 1].to_vec(),
+        },
+    };
+
+    // without other commits, the latest run is compared against the earlier runs of the same commit.
+    let latest_runs = if baseline_runs
+        .first()
+        .is_some_and(|v| v.commit == latest.commit)
+    {
+        vec![latest]
+    } else {
+        latest_runs
+    };
+
+    let Some(baseline_first) = baseline_runs.first() else {
+        println!(
+            "No comparable baseline runs found (same input, machine and {} build).",
+            latest.profile
+        );
+        return true;
+    };
+
+    println!(
+        "\t{}: {} ({} runs)",
+        "Baseline".green(),
+        baseline_first.commit.bold().blue(),
+        baseline_runs.len()
+    );
+    println!(
+        "\t{}:   {} ({} runs)",
+        "Latest".green(),
+        latest.commit.bold().blue(),
+        latest_runs.len()
+    );
+
+    let phases: [(&str, PhaseGetter); 3] = [
+        ("Parsing", |v| Some(v.init)),
+        ("Part 1", |v| v.one),
+        ("Part 2", |v| v.two),
+    ];
+
+    let mut ok = true;
+    for (name, get) in phases {
+        let collect = |runs: &[&RunRecord]| -> Vec<f64> {
+            runs.iter()
+                .filter_map(|v| get(v))
+                .map(|v| v.as_nanos() as f64)
+                .collect()
+        };
+        let (baseline_samples, latest_samples) = (collect(&baseline_runs), collect(&latest_runs));
+        if baseline_samples.is_empty() || latest_samples.is_empty() {
+            continue;
+        }
+
+        let (change, verdict) = compare_samples(
+            &baseline_samples,
+            &latest_samples,
+            threshold_percent / 100.0,
+        );
+        let (b_mean, _) = mean_and_variance(&baseline_samples);
+        let (l_mean, _) = mean_and_variance(&latest_samples);
+
+        let verdict = match verdict {
+            Verdict::Faster => "FASTER".green().bold(),
+            Verdict::Unchanged => "UNCHANGED".blue().bold(),
+            Verdict::PossiblySlower => "POSSIBLY SLOWER (too few samples)".yellow().bold(),
+            Verdict::Slower => {
+                ok = false;
+                "SLOWER".red().bold()
+            }
+        };
+
+        println!("\t{}: {}", name.green(), verdict);
+        println!(
+            "\t\t{} -> {} ({:+.1}%)",
+            dynamic_range_time_format(&Duration::from_nanos(b_mean as u64)),
+            dynamic_range_time_format(&Duration::from_nanos(l_mean as u64)),
+            change * 100.0
+        );
+    }
+
+    ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn welch_t_test_matches_hand_computed_values() {
+        // Means 11 and 15, both variances 2.5: t = 4 / sqrt(0.5 + 0.5) = 4 with 8 degrees of freedom.
+        let (t, df) = welch_t_test(
+            &[10.0, 12.0, 11.0, 13.0, 9.0],
+            &[14.0, 15.0, 13.0, 16.0, 17.0],
+        )
+        .unwrap();
+        assert_close(t, 4.0);
+        assert_close(df, 8.0);
+
+        // Means 100 and 101.4, variances 2.5 and 10.3: t = 1.4 / 1.6 = 0.875.
+        let (t, df) = welch_t_test(
+            &[100.0, 102.0, 98.0, 101.0, 99.0],
+            &[101.0, 105.0, 97.0, 104.0, 100.0],
+        )
+        .unwrap();
+        assert_close(t, 0.875);
+        assert!((df - 5.8337).abs() < 1e-4);
+    }
+
+    #[test]
+    fn welch_t_test_without_variance() {
+        assert_eq!(welch_t_test(&[5.0, 5.0], &[7.0, 7.0]), None);
+    }
+
+    #[test]
+    fn t_critical_values() {
+        assert_close(t_critical(1.0), 6.314);
+        assert_close(t_critical(8.0), 1.860);
+        assert_close(t_critical(5.83), 2.015);
+        assert_close(t_critical(1000.0), 1.645);
+    }
+
+    #[test]
+    fn compare_samples_verdicts() {
+        // t = 4 at 8 degrees of freedom: the one-sided p value is 0.002, a significant slowdown.
+        let (change, verdict) = compare_samples(
+            &[10.0, 12.0, 11.0, 13.0, 9.0],
+            &[14.0, 15.0, 13.0, 16.0, 17.0],
+            0.05,
+        );
+        assert_close(change, 4.0 / 11.0);
+        assert_eq!(verdict, Verdict::Slower);
+
+        // t = 0.875 at 5.8 degrees of freedom: p = 0.21, so the 1.4% slowdown is just noise.
+        let (_, verdict) = compare_samples(
+            &[100.0, 102.0, 98.0, 101.0, 99.0],
+            &[101.0, 105.0, 97.0, 104.0, 100.0],
+            0.01,
+        );
+        assert_eq!(verdict, Verdict::Unchanged);
+
+        // Below the threshold, no test is needed.
+        let (_, verdict) = compare_samples(
+            &[10.0, 12.0, 11.0, 13.0, 9.0],
+            &[14.0, 15.0, 13.0, 16.0, 17.0],
+            0.5,
+        );
+        assert_eq!(verdict, Verdict::Unchanged);
+
+        let (_, verdict) = compare_samples(&[20.0, 22.0], &[10.0, 11.0], 0.05);
+        assert_eq!(verdict, Verdict::Faster);
+
+        let (_, verdict) = compare_samples(&[10.0], &[20.0, 21.0], 0.05);
+        assert_eq!(verdict, Verdict::PossiblySlower);
+
+        let (_, verdict) = compare_samples(&[10.0, 10.0], &[20.0, 20.0], 0.05);
+        assert_eq!(verdict, Verdict::Slower);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::PartTestResult;
+use regex::Regex;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Escapes text for use in XML attributes and elements.
+///
+/// ANSI escape sequences (e.g. from colored log output) and other control characters are removed,
+/// as they aren't allowed in XML documents.
+fn escape_xml(value: &str) -> String {
+    let ansi = Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap();
+
+    ansi.replace_all(value, "")
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+        .map(|c| match c {
+            '&' => "&amp;".to_owned(),
+            '<' => "&lt;".to_owned(),
+            '>' => "&gt;".to_owned(),
+            '"' => "&quot;".to_owned(),
+            '\'' => "&apos;".to_owned(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Writes the results of testing a day as a JUnit XML report.
+///
+/// Each day is a test suite, with a test case for every part and example.
+/// Failed test cases include the result, the expected result and the captured output.
+pub fn write_junit_report(day: u8, results: &[PartTestResult], path: &Path) -> std::io::Result<()> {
+    let failures = results.iter().filter(|v| !v.passed).count();
+    let time: f64 = results.iter().map(|v| v.duration.as_secs_f64()).sum();
+    let suite = format!("day{:02}", day);
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"aoc25\" tests=\"{}\" failures=\"{}\" time=\"{:.6}\">",
+        results.len(),
+        failures,
+        time
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.6}\">",
+        suite,
+        results.len(),
+        failures,
+        time
+    );
+
+    for result in results {
+        let _ = write!(
+            out,
+            "    <testcase classname=\"{}.example{}\" name=\"part{}\" time=\"{:.6}\"",
+            suite,
+            result.example,
+            result.part,
+            result.duration.as_secs_f64()
+        );
+
+        if result.passed {
+            out += "/>\n";
+            continue;
+        }
+
+        let result_str = escape_xml(&result.result.to_string());
+        let expected_str = escape_xml(&result.expected.to_string());
+        out += ">\n";
+        let _ = writeln!(
+            out,
+            "      <failure message=\"expected {}, got {}\" type=\"WrongAnswer\">Result:   {}\nExpected: {}</failure>",
+            expected_str.trim(),
+            result_str.trim(),
+            result_str,
+            expected_str
+        );
+        if !result.output.is_empty() {
+            let _ = writeln!(
+                out,
+                "      <system-out>{}</system-out>",
+                escape_xml(&result.output)
+            );
+        }
+        out += "    </testcase>\n";
+    }
+
+    out += "  </testsuite>\n</testsuites>\n";
+
+    fs::write(path, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::Answer;
+    use std::time::Duration;
+
+    fn part_result(part: u8, example: usize, passed: bool, output: &str) -> PartTestResult {
+        PartTestResult {
+            part,
+            example,
+            passed,
+            result: Answer::from(1_u64),
+            expected: Answer::from(2_u64),
+            output: output.to_owned(),
+            duration: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn escapes_markup_and_strips_control_characters() {
+        assert_eq!(
+            escape_xml("a < b & \"c\" > 'd'"),
+            "a &lt; b &amp; &quot;c&quot; &gt; &apos;d&apos;"
+        );
+        assert_eq!(escape_xml("\x1b[1;31mred\x1b[0m\x07\tok\n"), "red\tok\n");
+    }
+
+    #[test]
+    fn report_names_each_example_and_escapes_output() {
+        let path = std::env::temp_dir().join(format!("aoc25_test_{}.xml", std::process::id()));
+        let results = [
+            part_result(1, 1, true, ""),
+            part_result(1, 2, false, "x < y && name == \"z\""),
+        ];
+        write_junit_report(3, &results, &path).unwrap();
+        let report = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(report.contains("<testcase classname=\"day03.example1\" name=\"part1\""));
+        assert!(report.contains("<testcase classname=\"day03.example2\" name=\"part1\""));
+        assert!(report.contains("tests=\"2\" failures=\"1\""));
+        assert!(report.contains("<failure message=\"expected 2, got 1\""));
+        assert!(
+            report.contains("<system-out>x &lt; y &amp;&amp; name == &quot;z&quot;</system-out>")
+        );
+    }
+}
@@ -1,13 +1,23 @@
-use crate::days::Answer;
-use crate::days::Day;
-use crate::days::DayImpl;
+pub use crate::days::Answer;
+pub use crate::days::Day;
+pub use crate::days::DayImpl;
+pub use crate::days::utils;
 use aoc_macro::*;
 use colored::*;
 use lazy_static::lazy_static;
 use mut_static::MutStatic;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 mod days;
+mod history;
+mod junit;
+pub mod logging;
+pub mod memo;
+mod visualize;
+
+pub use history::{compare_history, record_history};
+pub use junit::write_junit_report;
+pub use visualize::{VisualizationOutput, visualize_day};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Verbosity {
@@ -23,6 +33,29 @@ pub enum Part {
     Both,
 }
 
+/// A single step of a day's solution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Parse,
+    One,
+    Two,
+}
+
+/// When to stop repeating a phase while profiling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileLimit {
+    Iterations(u64),
+    Duration(Duration),
+}
+
+/// The time each step of a run took. Parts that weren't computed are `None`.
+#[derive(Debug, Clone)]
+pub struct RunTimings {
+    pub init: Duration,
+    pub one: Option<Duration>,
+    pub two: Option<Duration>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Settings {
     verbosity: Verbosity,
@@ -52,11 +85,13 @@ pub fn get_verbosity() -> Verbosity {
 #[macro_export]
 macro_rules! vprintln {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "tracing")]
+        if ::tracing::enabled!(::tracing::Level::DEBUG) {
+            $crate::logging::write_log(::tracing::Level::DEBUG, module_path!(), format_args!("{}\n", format_args!($($arg)*)))
+        }
+        #[cfg(all(debug_assertions, not(feature = "tracing")))]
         if $crate::get_verbosity() ==  $crate::Verbosity::Verbose || $crate::get_verbosity() ==  $crate::Verbosity::Development {
-            println!(
-                $($arg)*
-            )
+            $crate::logging::write_output(format_args!("{}\n", format_args!($($arg)*)))
         }
     };
 }
@@ -64,11 +99,13 @@ macro_rules! vprintln {
 #[macro_export]
 macro_rules! vprint {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "tracing")]
+        if ::tracing::enabled!(::tracing::Level::DEBUG) {
+            $crate::logging::write_log(::tracing::Level::DEBUG, module_path!(), format_args!($($arg)*))
+        }
+        #[cfg(all(debug_assertions, not(feature = "tracing")))]
         if $crate::get_verbosity() ==  $crate::Verbosity::Verbose || $crate::get_verbosity() ==  $crate::Verbosity::Development {
-            print!(
-                $($arg)*
-            )
+            $crate::logging::write_output(format_args!($($arg)*))
         }
     };
 }
@@ -76,11 +113,13 @@ macro_rules! vprint {
 #[macro_export]
 macro_rules! dprintln {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "tracing")]
+        if ::tracing::enabled!(::tracing::Level::TRACE) {
+            $crate::logging::write_log(::tracing::Level::TRACE, module_path!(), format_args!("{}\n", format_args!($($arg)*)))
+        }
+        #[cfg(all(debug_assertions, not(feature = "tracing")))]
         if $crate::get_verbosity() == $crate::Verbosity::Development  {
-            println!(
-                $($arg)*
-            )
+            $crate::logging::write_output(format_args!("{}\n", format_args!($($arg)*)))
         }
     };
 }
@@ -88,15 +127,35 @@ macro_rules! dprintln {
 #[macro_export]
 macro_rules! dprint {
     ($($arg:tt)*) => {
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "tracing")]
+        if ::tracing::enabled!(::tracing::Level::TRACE) {
+            $crate::logging::write_log(::tracing::Level::TRACE, module_path!(), format_args!($($arg)*))
+        }
+        #[cfg(all(debug_assertions, not(feature = "tracing")))]
         if $crate::get_verbosity() == $crate::Verbosity::Development  {
-            print!(
-                $($arg)*
-            )
+            $crate::logging::write_output(format_args!($($arg)*))
         }
     };
 }
 
+/// Enters a tracing span, which is left when the returned guard is dropped.
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        $crate::logging::SpanGuard::new(::tracing::info_span!($($arg)*))
+    };
+}
+
+/// Does nothing, as the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        $crate::logging::SpanGuard
+    };
+}
+
 fn dynamic_range_time_format(d: &Duration) -> String {
     let nanos = d.as_nanos();
 
@@ -124,7 +183,8 @@ fn dynamic_range_time_format(d: &Duration) -> String {
     }
 }
 
-pub fn run_day(day: u8, part: Part, input: &str) {
+pub fn run_day(day: u8, part: Part, input: &str) -> RunTimings {
+    let _span = trace_span!("day", day);
     println!("{} Day {}", "Starting".green().bold(), day);
     println!("{}", "-----------------------".green().bold());
     let (one, two, init_t, one_t, two_t) = match part {
@@ -173,89 +233,259 @@ pub fn run_day(day: u8, part: Part, input: &str) {
             }
         );
     }
+
+    RunTimings {
+        init: init_t,
+        one: (part != Part::Two).then_some(one_t),
+        two: (part != Part::One).then_some(two_t),
+    }
 }
 
-pub fn test_day(day: u8, part: Part) -> bool {
-    println!("{} Day {}", "Testing".green().bold(), day);
+pub fn profile_day(day: u8, phase: Phase, limit: ProfileLimit, input: &str) {
+    let _span = trace_span!("day", day);
+    println!("{} Day {}", "Profiling".green().bold(), day);
     println!("{}", "-----------------------".green().bold());
-    match part {
-        Part::Both => {
-            let ((one_p, one_r, one_e), (two_p, two_r, two_e)) = match_and_test_day_both!();
-
-            println!("{}:", "Results".green().bold());
-            println!(
-                "\t{}: {}",
-                "Part 1".green(),
-                match one_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
-            );
-            println!("\t\tResult:   {}", format!("{}", one_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", one_e).bold().blue());
 
-            println!(
-                "\t{}: {}",
-                "Part 2".green(),
-                match two_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
-            );
-            println!("\t\tResult:   {}", format!("{}", two_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", two_e).bold().blue());
+    let (iterations, measured) = match_and_profile_day!();
 
-            !(!two_p || !one_p)
+    println!("{}:", "Results".green().bold());
+    println!(
+        "\t{}: {}",
+        "Phase".green(),
+        match phase {
+            Phase::Parse => "Parsing",
+            Phase::One => "Part 1",
+            Phase::Two => "Part 2",
         }
-        Part::One => {
-            let (one_p, one_r, one_e) = match_and_test_day_one!();
+        .bold()
+        .blue()
+    );
+    println!(
+        "\t{}: {}",
+        "Iterations".green(),
+        iterations.to_string().bold().blue()
+    );
+    let average = match iterations {
+        0 => Duration::ZERO,
+        n => Duration::from_nanos((measured.as_nanos() / n as u128) as u64),
+    };
+    println!(
+        "\t{}: {}",
+        "Average".green(),
+        dynamic_range_time_format(&average).bold().blue()
+    );
+}
+
+/// Validates an input against the day's expected format, and prints every problem found.
+///
+/// Returns false if any problems were found.
+pub fn check_input(day: u8, input: &str) -> bool {
+    println!("{} Day {}", "Checking".green().bold(), day);
+    println!("{}", "-----------------------".green().bold());
+
+    let mut problems = days::validate_common(input);
+    let day_problems = match_and_validate_day!();
+    let has_validator = day_problems.is_some();
+    problems.extend(day_problems.unwrap_or_default());
+    problems.sort_by_key(|v| v.line);
+
+    if !has_validator {
+        println!(
+            "{}",
+            format!(
+                "Day {} has no validator, only common problems were checked.",
+                day
+            )
+            .yellow()
+        );
+    }
+
+    if problems.is_empty() {
+        println!("{}", "No problems found.".green().bold());
+        return true;
+    }
+
+    println!(
+        "{}:",
+        format!(
+            "Found {} problem{}",
+            problems.len(),
+            if problems.len() == 1 { "" } else { "s" }
+        )
+        .red()
+        .bold()
+    );
+    for problem in &problems {
+        println!("\t{}", problem);
+    }
+
+    false
+}
+
+/// Generates a random input for a day, or returns `None` if the day has no input generator.
+pub fn generate_input(day: u8, seed: u64, size: usize) -> Option<String> {
+    let mut rng = days::utils::Rng::new(seed);
+    match_and_generate_day!()
+}
+
+/// Compares the optimized solutions of a day with its reference implementations on each input,
+/// and reports the first input on which they disagree.
+///
+/// Inputs are `(name, input)` pairs, where an input of `None` uses the example input.
+/// Returns false if they disagree on any input.
+pub fn crosscheck_day(day: u8, part: Part, inputs: &[(String, Option<String>)]) -> bool {
+    let _span = trace_span!("day", day);
+    println!("{} Day {}", "Cross-checking".green().bold(), day);
+    println!("{}", "-----------------------".green().bold());
+
+    let mut checked_any = false;
+    for (name, input) in inputs {
+        let input = input.as_deref();
+        let results = match_and_crosscheck_day!();
+
+        for (i, result) in results.into_iter().enumerate() {
+            let part_number = i + 1;
+            if (part == Part::One && part_number != 1) || (part == Part::Two && part_number != 2) {
+                continue;
+            }
+            let Some((optimized, reference)) = result else {
+                continue;
+            };
+            checked_any = true;
+
+            if optimized != reference {
+                println!(
+                    "\t{} on {}: {}",
+                    format!("Part {}", part_number).green(),
+                    name.bold(),
+                    "DISAGREE".red().bold()
+                );
+                println!("\t\tOptimized: {}", format!("{}", optimized).bold().blue());
+                println!("\t\tReference: {}", format!("{}", reference).bold().blue());
+                return false;
+            }
 
-            println!("{}:", "Results".green().bold());
             println!(
-                "\t{}: {}",
-                "Part 1".green(),
-                match one_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
+                "\t{} on {}: {}",
+                format!("Part {}", part_number).green(),
+                name.bold(),
+                "AGREE".green().bold()
             );
-            println!("\t\tResult:   {}", format!("{}", one_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", one_e).bold().blue());
+        }
+    }
+
+    if !checked_any {
+        println!(
+            "Day {} has no reference implementations to cross-check.",
+            day
+        );
+    }
+
+    true
+}
 
-            !one_p
+/// The result of testing a single part of a day on an example.
+#[derive(Debug, Clone)]
+pub struct PartTestResult {
+    pub part: u8,
+    pub example: usize,
+    pub passed: bool,
+    pub result: Answer,
+    pub expected: Answer,
+    /// Verbose and development output printed while testing.
+    pub output: String,
+    pub duration: Duration,
+}
+
+/// Prints the result of testing a single part, including the captured output if it failed.
+fn print_test_result(result: &PartTestResult) {
+    println!(
+        "\t{}: {}",
+        match result.example {
+            1 => format!("Part {}", result.part),
+            example => format!("Part {}, example {}", result.part, example),
         }
-        Part::Two => {
-            let (two_p, two_r, two_e) = match_and_test_day_two!();
+        .green(),
+        match result.passed {
+            true => {
+                "PASSED".green().bold()
+            }
+            false => {
+                "FAILED".red().bold()
+            }
+        }
+    );
+    println!(
+        "\t\tResult:   {}",
+        format!("{}", result.result).bold().blue()
+    );
+    println!(
+        "\t\tExpected: {}",
+        format!("{}", result.expected).bold().blue()
+    );
 
-            println!("{}:", "Results".green().bold());
-            println!(
-                "\t{}: {}",
-                "Part 2".green(),
-                match two_p {
-                    true => {
-                        "PASSED".green().bold()
-                    }
-                    false => {
-                        "FAILED".red().bold()
-                    }
-                }
-            );
-            println!("\t\tResult:   {}", format!("{}", two_r).bold().blue());
-            println!("\t\tExpected: {}", format!("{}", two_e).bold().blue());
+    if !result.passed && !result.output.is_empty() {
+        println!("\t\t{}:", "Output".yellow());
+        for line in result.output.trim_end().lines() {
+            println!("\t\t\t{}", line);
+        }
+    }
+}
+
+pub fn test_day(day: u8, part: Part) -> Vec<PartTestResult> {
+    let _span = trace_span!("day", day);
+    println!("{} Day {}", "Testing".green().bold(), day);
+    println!("{}", "-----------------------".green().bold());
 
-            !two_p
+    // Each part and example is tested on its own, so the output captured while testing it can be shown with it.
+    let mut results = Vec::new();
+    if part != Part::Two {
+        for example in 1.. {
+            logging::start_capture();
+            let s = Instant::now();
+            let tested = match_and_test_day_one!();
+            let duration = s.elapsed();
+            let output = logging::finish_capture();
+            let Some((passed, result, expected)) = tested else {
+                break;
+            };
+            results.push(PartTestResult {
+                part: 1,
+                example,
+                passed,
+                result,
+                expected,
+                duration,
+                output,
+            });
+        }
+    }
+    if part != Part::One {
+        for example in 1.. {
+            logging::start_capture();
+            let s = Instant::now();
+            let tested = match_and_test_day_two!();
+            let duration = s.elapsed();
+            let output = logging::finish_capture();
+            let Some((passed, result, expected)) = tested else {
+                break;
+            };
+            results.push(PartTestResult {
+                part: 2,
+                example,
+                passed,
+                result,
+                expected,
+                duration,
+                output,
+            });
         }
     }
+
+    println!("{}:", "Results".green().bold());
+    for result in &results {
+        print_test_result(result);
+    }
+
+    results
 }
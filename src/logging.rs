@@ -0,0 +1,219 @@
+//! Structured logging using [`tracing`](https://docs.rs/tracing), enabled by the `tracing` feature.
+//!
+//! Without the feature, [`vprintln!`](crate::vprintln) and [`dprintln!`](crate::dprintln) print
+//! directly in debug builds and are compiled out in release builds, and spans do nothing.
+//!
+//! In both cases, their output can be captured using [`start_capture`] and [`finish_capture`].
+
+#[cfg(feature = "tracing")]
+use crate::{Verbosity, get_verbosity};
+use std::cell::RefCell;
+use std::path::Path;
+
+thread_local! {
+    static CAPTURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Starts capturing the verbose and development output of the current thread, instead of printing it.
+pub fn start_capture() {
+    CAPTURE.with_borrow_mut(|capture| *capture = Some(String::new()));
+}
+
+/// Stops capturing output, and returns everything captured since [`start_capture`].
+pub fn finish_capture() -> String {
+    CAPTURE
+        .with_borrow_mut(|capture| capture.take())
+        .unwrap_or_default()
+}
+
+/// Appends to the capture buffer if output is being captured, or prints to stdout otherwise.
+pub fn write_output(args: std::fmt::Arguments) {
+    let captured = CAPTURE.with_borrow_mut(|capture| match capture {
+        Some(buffer) => {
+            std::fmt::Write::write_fmt(buffer, args).unwrap();
+            true
+        }
+        None => false,
+    });
+
+    if !captured {
+        print!("{}", args);
+    }
+}
+
+#[cfg(feature = "tracing")]
+thread_local! {
+    /// The unfinished lines printed at the debug and the trace level.
+    static PARTIAL_LINES: RefCell<[String; 2]> = const { RefCell::new([String::new(), String::new()]) };
+}
+
+/// Logs everything up to the last newline as one event per line, at the debug or the trace level.
+///
+/// The rest is kept until its line is finished, so a line printed in pieces becomes a single event.
+/// As the events are created here, the module which printed them is recorded as the `module` field.
+#[cfg(feature = "tracing")]
+pub fn write_log(level: tracing::Level, module: &str, args: std::fmt::Arguments) {
+    let index = match level {
+        tracing::Level::DEBUG => 0,
+        tracing::Level::TRACE => 1,
+        _ => panic!("Only debug and trace prints are buffered."),
+    };
+
+    let lines: Vec<String> = PARTIAL_LINES.with_borrow_mut(|partial| {
+        let buffer = &mut partial[index];
+        std::fmt::Write::write_fmt(buffer, args).unwrap();
+        take_complete_lines(buffer)
+    });
+
+    for line in lines {
+        match index {
+            0 => tracing::debug!(module, "{}", line),
+            _ => tracing::trace!(module, "{}", line),
+        }
+    }
+}
+
+/// Removes all finished lines from the buffer, leaving only the last unfinished one.
+#[cfg(feature = "tracing")]
+fn take_complete_lines(buffer: &mut String) -> Vec<String> {
+    let Some(end) = buffer.rfind('\n') else {
+        return Vec::new();
+    };
+    let complete: String = buffer.drain(..=end).collect();
+    complete.lines().map(str::to_owned).collect()
+}
+
+/// Writes logs to stderr, or to the capture buffer while output is being captured.
+#[cfg(feature = "tracing")]
+struct TerminalWriter;
+
+#[cfg(feature = "tracing")]
+impl std::io::Write for TerminalWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let captured = CAPTURE.with_borrow_mut(|capture| match capture {
+            Some(buffer) => {
+                buffer.push_str(&String::from_utf8_lossy(buf));
+                true
+            }
+            None => false,
+        });
+
+        if captured {
+            Ok(buf.len())
+        } else {
+            std::io::stderr().write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stderr().flush()
+    }
+}
+
+/// Keeps a span entered until it is dropped. Created using [`trace_span!`](crate::trace_span).
+#[cfg(feature = "tracing")]
+pub struct SpanGuard(#[allow(dead_code)] tracing::span::EnteredSpan);
+
+#[cfg(feature = "tracing")]
+impl SpanGuard {
+    pub fn new(span: tracing::Span) -> Self {
+        Self(span.entered())
+    }
+}
+
+/// Placeholder for a span guard, as the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub struct SpanGuard;
+
+/// Whether structured logging is available in this build.
+pub fn is_available() -> bool {
+    cfg!(feature = "tracing")
+}
+
+/// Formats fields without colors.
+///
+/// Formatted fields are cached per formatter type, so the file needs a different one than the terminal,
+/// or it would contain the terminal's escape codes.
+#[cfg(feature = "tracing")]
+struct PlainFields;
+
+#[cfg(feature = "tracing")]
+impl<'writer> tracing_subscriber::fmt::FormatFields<'writer> for PlainFields {
+    fn format_fields<R: tracing_subscriber::field::RecordFields>(
+        &self,
+        writer: tracing_subscriber::fmt::format::Writer<'writer>,
+        fields: R,
+    ) -> std::fmt::Result {
+        tracing_subscriber::fmt::format::DefaultFields::new().format_fields(writer, fields)
+    }
+}
+
+/// Sets up logging to stderr, and optionally to a file, based on the current verbosity.
+///
+/// Verbose prints are logged at the debug level and development prints at the trace level.
+/// The log file additionally contains the time spent in every span.
+///
+/// Returns an error if the log file can't be created, in which case only the terminal is logged to.
+#[cfg(feature = "tracing")]
+pub fn init_logging(log_file: Option<&Path>) -> std::io::Result<()> {
+    use std::fs::File;
+    use std::sync::Mutex;
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::prelude::*;
+
+    let level = match get_verbosity() {
+        Verbosity::None => LevelFilter::WARN,
+        Verbosity::Verbose => LevelFilter::DEBUG,
+        Verbosity::Development => LevelFilter::TRACE,
+    };
+
+    let terminal = tracing_subscriber::fmt::layer()
+        .with_writer(|| TerminalWriter)
+        .with_filter(level);
+
+    // The terminal is always logged to, even if the log file can't be created.
+    let (file, result) = match log_file.map(File::create).transpose() {
+        Ok(file) => (file, Ok(())),
+        Err(err) => (None, Err(err)),
+    };
+    let file = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(Mutex::new(file))
+            .with_ansi(false)
+            .fmt_fields(PlainFields)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_filter(level.max(LevelFilter::INFO))
+    });
+
+    tracing_subscriber::registry()
+        .with(terminal)
+        .with(file)
+        .init();
+
+    result
+}
+
+/// Does nothing, as the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub fn init_logging(_log_file: Option<&Path>) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_printed_in_pieces_are_joined() {
+        let mut buffer = String::new();
+
+        buffer.push('a');
+        assert!(take_complete_lines(&mut buffer).is_empty());
+        buffer.push('b');
+        assert!(take_complete_lines(&mut buffer).is_empty());
+        buffer.push_str("c\nd\n\ne");
+        assert_eq!(take_complete_lines(&mut buffer), vec!["abc", "d", ""]);
+        assert_eq!(buffer, "e");
+    }
+}
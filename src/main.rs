@@ -1,5 +1,9 @@
-use aoc25::{run_day, set_verbosity, test_day, Part, Verbosity};
-use clap::{App, AppSettings, Arg, SubCommand};
+use aoc25::{
+    Part, Phase, ProfileLimit, Verbosity, VisualizationOutput, check_input, compare_history,
+    crosscheck_day, generate_input, logging, profile_day, record_history, run_day, set_verbosity,
+    test_day, visualize_day, write_junit_report,
+};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use colored::*;
 use reqwest::blocking::Client;
 use reqwest::cookie::Jar;
@@ -8,6 +12,7 @@ use std::env;
 use std::fs;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // NOTE: Since this CLI was coded using clap in december of 2021, there seem to
 // have been huge breaking changes, requiring me to rewrite the CLI completely
@@ -58,8 +63,18 @@ fn main() {
                 .long("dev")
                 .short("d")
                 .conflicts_with("verbose"))
+        .arg(
+            Arg::with_name("log_file")
+                .help("Additionally write logs, including the time spent in each span, to a file. Requires the `tracing` feature.")
+                .long("log-file")
+                .takes_value(true))
         .subcommand(
-            SubCommand::with_name("test").about("Test the day with the example input data."),
+            SubCommand::with_name("test")
+                .about("Test the day with the example input data.")
+                .arg(Arg::with_name("junit")
+                    .help("Additionally write the results as a JUnit XML report to this file.")
+                    .long("junit")
+                    .takes_value(true)),
         )
         .subcommand(
             SubCommand::with_name("auto")
@@ -83,10 +98,128 @@ fn main() {
                     .takes_value(true)
             )
         )
+        .subcommand(
+            SubCommand::with_name("profile")
+                .about("Run a single phase of the solution in a loop, for use with external profilers.")
+                .arg(Arg::with_name("phase")
+                    .help("The phase to profile.")
+                    .long("phase")
+                    .default_value("1")
+                    .possible_values(&["parse", "1", "2"])
+                    .takes_value(true))
+                .arg(Arg::with_name("iterations")
+                    .help("Stop after this many iterations.")
+                    .short("n")
+                    .long("iterations")
+                    .takes_value(true)
+                    .conflicts_with("duration")
+                    .validator(|v| match v.parse::<u64>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("The iterations must be a number.".to_string()),
+                    }))
+                .arg(Arg::with_name("duration")
+                    .help("Stop after this many seconds.")
+                    .short("t")
+                    .long("duration")
+                    .default_value("10")
+                    .takes_value(true)
+                    .validator(validate_duration))
+                .arg(Arg::with_name("file")
+                    .help("Specify a file to be used as input, otherwise use stdin.")
+                    .short("f")
+                    .long("file")
+                    .takes_value(true)))
+        .subcommand(
+            SubCommand::with_name("visualize")
+                .about("Visualize how the solution works, if supported by the day.")
+                .arg(Arg::with_name("file")
+                    .help("Specify a file to be used as input, otherwise use stdin.")
+                    .short("f")
+                    .long("file")
+                    .takes_value(true)
+                    .conflicts_with("example"))
+                .arg(Arg::with_name("example")
+                    .help("Use the example input.")
+                    .short("e")
+                    .long("example"))
+                .arg(Arg::with_name("format")
+                    .help("Play the visualization in the terminal, or write it to an asciicast file or a directory of PPM images.")
+                    .long("format")
+                    .default_value("terminal")
+                    .possible_values(&["terminal", "cast", "ppm"])
+                    .takes_value(true))
+                .arg(Arg::with_name("output")
+                    .help("The file or directory to write the visualization to.")
+                    .short("o")
+                    .long("output")
+                    .required_ifs(&[("format", "cast"), ("format", "ppm")])
+                    .takes_value(true))
+                .arg(Arg::with_name("fps")
+                    .help("The number of frames shown per second.")
+                    .long("fps")
+                    .default_value("10")
+                    .takes_value(true)
+                    .validator(validate_fps)))
+        .subcommand(
+            SubCommand::with_name("crosscheck")
+                .about("Compare the solution with the day's reference implementations on the example, the cached input and any provided files.")
+                .arg(Arg::with_name("file")
+                    .help("Additional files to be used as input.")
+                    .short("f")
+                    .long("file")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1))
+                .arg(Arg::with_name("generate")
+                    .help("Additionally check this many generated inputs.")
+                    .short("g")
+                    .long("generate")
+                    .default_value("0")
+                    .takes_value(true)
+                    .validator(validate_number))
+                .arg(size_arg())
+                .arg(seed_arg()))
+        .subcommand(
+            SubCommand::with_name("gen")
+                .about("Generate a random input for the day.")
+                .arg(size_arg())
+                .arg(seed_arg())
+                .arg(Arg::with_name("output")
+                    .help("The file to write the input to, otherwise use stdout.")
+                    .short("o")
+                    .long("output")
+                    .takes_value(true)))
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("Use either a file or stdin as input and check it for problems, without solving it.")
+                .arg(Arg::with_name("file")
+                    .help("Specify a file to be checked, otherwise use stdin.")
+                    .short("f")
+                    .long("file")
+                    .takes_value(true)))
+        .subcommand(
+            SubCommand::with_name("compare")
+                .about("Compare the latest recorded run of the day to a baseline, and flag significant slowdowns.")
+                .arg(Arg::with_name("baseline")
+                    .help("The commit to compare against. Defaults to the runs of the previous commit.")
+                    .short("b")
+                    .long("baseline")
+                    .takes_value(true))
+                .arg(Arg::with_name("threshold")
+                    .help("The minimum slowdown in percent to be reported.")
+                    .short("t")
+                    .long("threshold")
+                    .default_value("5")
+                    .takes_value(true)
+                    .validator(|v| match v.parse::<f64>() {
+                        Ok(t) if t >= 0.0 => Ok(()),
+                        _ => Err("The threshold must be a positive number.".to_string()),
+                    })))
         .get_matches();
 
+    // Printed to stderr, so it doesn't end up in output like generated inputs.
     if cfg!(debug_assertions) {
-        println!(
+        eprintln!(
             "{}",
             "This binary was built in debug mode. To improve performance, please add --release to the build command."
                 .red()
@@ -108,17 +241,27 @@ fn main() {
     };
 
     if matches.args.contains_key("verbose") {
-        #[cfg(not(debug_assertions))]
-        println!("{}", "verbosity and development prints are only available in unoptimized builds (compiled without --release).".red().bold());
+        if !cfg!(debug_assertions) && !logging::is_available() {
+            println!("{}", "verbosity and development prints are only available in unoptimized builds (compiled without --release), or builds with the `tracing` feature.".red().bold());
+        }
         set_verbosity(Verbosity::Verbose);
     }
 
     if matches.args.contains_key("development") {
-        #[cfg(not(debug_assertions))]
-        println!("{}", "verbosity and development prints are only available in unoptimized builds (compiled without --release).".red().bold());
+        if !cfg!(debug_assertions) && !logging::is_available() {
+            println!("{}", "verbosity and development prints are only available in unoptimized builds (compiled without --release), or builds with the `tracing` feature.".red().bold());
+        }
         set_verbosity(Verbosity::Development);
     }
 
+    let log_file = matches.value_of("log_file").map(Path::new);
+    if log_file.is_some() && !logging::is_available() {
+        println!("{}", "log files are only available in builds with the `tracing` feature (compiled with --features tracing).".red().bold());
+    }
+    if let Err(err) = logging::init_logging(log_file) {
+        println!("Warning! couldn't open log file!{:?}", err)
+    }
+
     match matches.subcommand() {
         ("run", c_matches) => {
             let input = match c_matches {
@@ -131,7 +274,8 @@ fn main() {
                 }
                 None => get_stdin_day_input(day),
             };
-            run_day(day, part, &input);
+            let timings = run_day(day, part.clone(), &input);
+            save_history(day, &part, &input, &timings);
         }
         ("auto", c_matches) => {
             let session: Option<String> = match c_matches {
@@ -148,10 +292,152 @@ fn main() {
             };
 
             let input = get_auto_input(day, session.as_ref(), cache);
-            run_day(day, part, &input);
+            let timings = run_day(day, part.clone(), &input);
+            save_history(day, &part, &input, &timings);
+        }
+        ("test", c_matches) => {
+            let results = test_day(day, part);
+            if let Some(path) = c_matches.and_then(|v| v.value_of("junit"))
+                && let Err(err) = write_junit_report(day, &results, Path::new(path))
+            {
+                println!("Warning! couldn't write JUnit report!{:?}", err)
+            }
+            if !results.iter().all(|v| v.passed) {
+                std::process::exit(1);
+            }
+        }
+        ("profile", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let input = if let Some(f) = c_matches.value_of("file") {
+                fs::read_to_string(Path::new(f)).expect("Error while reading input file")
+            } else {
+                get_stdin_day_input(day)
+            };
+            let phase = match c_matches.value_of("phase") {
+                Some("parse") => Phase::Parse,
+                Some("1") => Phase::One,
+                Some("2") => Phase::Two,
+                _ => panic!("unexpected phase argument."),
+            };
+            let limit = match c_matches.value_of("iterations") {
+                Some(n) => ProfileLimit::Iterations(n.parse().unwrap()),
+                None => ProfileLimit::Duration(
+                    parse_duration(c_matches.value_of("duration").unwrap()).unwrap(),
+                ),
+            };
+            profile_day(day, phase, limit, &input);
+        }
+        ("visualize", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let input = if c_matches.is_present("example") {
+                None
+            } else if let Some(f) = c_matches.value_of("file") {
+                Some(fs::read_to_string(Path::new(f)).expect("Error while reading input file"))
+            } else {
+                Some(get_stdin_day_input(day))
+            };
+            let output = match (c_matches.value_of("format"), c_matches.value_of("output")) {
+                (Some("cast"), Some(path)) => VisualizationOutput::Asciicast(path.to_owned()),
+                (Some("ppm"), Some(path)) => VisualizationOutput::Images(path.to_owned()),
+                _ => VisualizationOutput::Terminal,
+            };
+            let fps = c_matches
+                .value_of("fps")
+                .unwrap()
+                .parse()
+                .expect("Failed to parse fps argument.");
+
+            match visualize_day(day, input.as_deref(), output, fps) {
+                Ok(true) => {}
+                Ok(false) => {
+                    println!(
+                        "{}",
+                        format!("Day {} does not support visualization.", day)
+                            .red()
+                            .bold()
+                    );
+                    std::process::exit(1);
+                }
+                Err(err) => panic!("Error while writing visualization: {:?}", err),
+            }
+        }
+        ("crosscheck", c_matches) => {
+            let mut inputs = vec![("example".to_owned(), None)];
+            let cache_path = format!("./.aoc25_cache/input{:02}.txt", day);
+            if let Ok(input) = fs::read_to_string(Path::new(&cache_path)) {
+                inputs.push(("cached input".to_owned(), Some(input)));
+            }
+            for f in c_matches
+                .and_then(|v| v.values_of("file"))
+                .into_iter()
+                .flatten()
+            {
+                let input =
+                    fs::read_to_string(Path::new(f)).expect("Error while reading input file");
+                inputs.push((f.to_owned(), Some(input)));
+            }
+
+            let c_matches = c_matches.unwrap();
+            let count: u64 = c_matches.value_of("generate").unwrap().parse().unwrap();
+            let size = c_matches.value_of("size").unwrap().parse().unwrap();
+            let seed = get_seed(c_matches);
+            for i in 0..count {
+                match generate_input(day, seed.wrapping_add(i), size) {
+                    Some(input) => inputs.push((
+                        format!("generated input (seed {})", seed.wrapping_add(i)),
+                        Some(input),
+                    )),
+                    None => {
+                        println!("Day {} has no input generator.", day);
+                        break;
+                    }
+                }
+            }
+
+            if !crosscheck_day(day, part, &inputs) {
+                std::process::exit(1);
+            }
+        }
+        ("gen", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let size = c_matches.value_of("size").unwrap().parse().unwrap();
+            let seed = get_seed(c_matches);
+
+            let Some(input) = generate_input(day, seed, size) else {
+                println!(
+                    "{}",
+                    format!("Day {} has no input generator.", day).red().bold()
+                );
+                std::process::exit(1);
+            };
+
+            match c_matches.value_of("output") {
+                Some(f) => {
+                    fs::write(Path::new(f), input + "\n").expect("Error while writing input file");
+                    println!("Generated input for day {} using seed {}.", day, seed);
+                }
+                None => println!("{}", input),
+            }
+        }
+        ("check", c_matches) => {
+            let input = if let Some(f) = c_matches.and_then(|v| v.value_of("file")) {
+                fs::read_to_string(Path::new(f)).expect("Error while reading input file")
+            } else {
+                get_stdin_day_input(day)
+            };
+
+            if !check_input(day, &input) {
+                std::process::exit(1);
+            }
         }
-        ("test", _) => {
-            if !test_day(day, part) {
+        ("compare", c_matches) => {
+            let c_matches = c_matches.unwrap();
+            let threshold = c_matches
+                .value_of("threshold")
+                .unwrap()
+                .parse::<f64>()
+                .expect("Failed to parse threshold argument.");
+            if !compare_history(day, c_matches.value_of("baseline"), threshold) {
                 std::process::exit(1);
             }
         }
@@ -159,6 +445,78 @@ fn main() {
     }
 }
 
+fn save_history(day: u8, part: &Part, input: &str, timings: &aoc25::RunTimings) {
+    if let Err(err) = record_history(day, part, input, timings) {
+        println!("Warning! couldn't save run to history!{:?}", err)
+    }
+}
+
+fn validate_number(v: String) -> Result<(), String> {
+    match v.parse::<u64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err("The value must be a number.".to_string()),
+    }
+}
+
+fn validate_positive_number(v: String) -> Result<(), String> {
+    match v.parse::<u64>() {
+        Ok(0) => Err("The value must be at least 1.".to_string()),
+        Ok(_) => Ok(()),
+        Err(_) => Err("The value must be a number.".to_string()),
+    }
+}
+
+fn validate_fps(v: String) -> Result<(), String> {
+    match v.parse::<f64>() {
+        // Each frame is shown for 1 / fps seconds, which has to fit into a Duration.
+        Ok(fps) if fps.is_finite() && Duration::try_from_secs_f64(1.0 / fps).is_ok() => Ok(()),
+        _ => Err("The frame rate must be a positive number, and not too small.".to_string()),
+    }
+}
+
+/// Parses a number of seconds, returning None if it is negative or doesn't fit into a Duration.
+fn parse_duration(v: &str) -> Option<Duration> {
+    v.parse::<f64>()
+        .ok()
+        .and_then(|t| Duration::try_from_secs_f64(t).ok())
+}
+
+fn validate_duration(v: String) -> Result<(), String> {
+    match parse_duration(&v) {
+        Some(_) => Ok(()),
+        None => Err(
+            "The duration must be a non-negative number of seconds, and not too large.".to_string(),
+        ),
+    }
+}
+
+fn size_arg() -> Arg<'static, 'static> {
+    Arg::with_name("size")
+        .help("The size of generated inputs. Its meaning depends on the day, e.g. the number of lines.")
+        .long("size")
+        .default_value("100")
+        .takes_value(true)
+        .validator(validate_positive_number)
+}
+
+fn seed_arg() -> Arg<'static, 'static> {
+    Arg::with_name("seed")
+        .help("The seed for generated inputs. Uses the current time if not provided.")
+        .long("seed")
+        .takes_value(true)
+        .validator(validate_number)
+}
+
+fn get_seed(matches: &ArgMatches) -> u64 {
+    match matches.value_of("seed") {
+        Some(seed) => seed.parse().unwrap(),
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|v| v.as_nanos() as u64)
+            .unwrap_or(0),
+    }
+}
+
 fn get_stdin_day_input(day: u8) -> String {
     let mut input = String::new();
     let stdin = std::io::stdin();
@@ -0,0 +1,187 @@
+//! Caches for functions annotated with [`#[memoize]`](aoc_macro::memoize).
+//!
+//! Every memoized function has its own thread local [`MemoCache`]. Instead of living for the
+//! whole program, its entries belong to the current scope, which [`DayImpl::solve`](crate::DayImpl::solve)
+//! starts anew before every solve. This way, results computed for one input (e.g. an example)
+//! are never returned for another one.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+thread_local! {
+    static SCOPE: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Starts a new scope on the current thread, which invalidates the entries of all memoized functions.
+pub fn new_scope() {
+    SCOPE.set(SCOPE.get().wrapping_add(1));
+}
+
+/// The results of a memoized function, computed within a single scope.
+#[derive(Debug)]
+pub struct MemoCache<K, V> {
+    scope: u64,
+    entries: HashMap<K, V>,
+}
+
+impl<K, V> MemoCache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            scope: SCOPE.get(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Drops all entries, if they were computed in a previous scope.
+    fn refresh(&mut self) {
+        let scope = SCOPE.get();
+        if self.scope != scope {
+            self.scope = scope;
+            self.entries.clear();
+        }
+    }
+
+    /// Returns the cached result for `key`, if it was computed in the current scope.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.refresh();
+        self.entries.get(key).cloned()
+    }
+
+    /// Stores the result for `key` in the current scope.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.refresh();
+        self.entries.insert(key, value);
+    }
+
+    /// The number of results cached in the current scope.
+    pub fn len(&mut self) -> usize {
+        self.refresh();
+        self.entries.len()
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, V> Default for MemoCache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, DayImpl};
+    use aoc_macro::memoize;
+
+    thread_local! {
+        static CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[memoize]
+    fn square(n: u64) -> u64 {
+        CALLS.set(CALLS.get() + 1);
+        n * n
+    }
+
+    /// Keyed on the index only, like a day keying on the position within its input.
+    #[memoize(key: usize = index)]
+    fn weighted_sum(index: usize, values: &[u64]) -> u64 {
+        (index as u64 + 1) * values.iter().sum::<u64>()
+    }
+
+    #[derive(Debug, Clone)]
+    struct Sums;
+
+    impl DayImpl<Vec<Vec<u64>>> for Sums {
+        fn init_test() -> (Self, Vec<Vec<u64>>) {
+            Self::init("1 2\n3")
+        }
+
+        fn expected_results() -> (Answer, Answer) {
+            (Answer::Number(9), Answer::Number(0))
+        }
+
+        fn init(input: &str) -> (Self, Vec<Vec<u64>>) {
+            let lines = input
+                .lines()
+                .map(|line| line.split(' ').map(|v| v.parse().unwrap()).collect())
+                .collect();
+            (Self, lines)
+        }
+
+        fn one(&self, data: &mut Vec<Vec<u64>>) -> Answer {
+            let sum: u64 = data
+                .iter()
+                .enumerate()
+                .map(|(i, v)| weighted_sum(i, v))
+                .sum();
+            Answer::Number(sum)
+        }
+
+        fn two(&self, _data: &mut Vec<Vec<u64>>) -> Answer {
+            Answer::Number(0)
+        }
+    }
+
+    #[test]
+    fn entries_are_cached_within_a_scope() {
+        new_scope();
+        CALLS.set(0);
+        assert_eq!(square(3), 9);
+        assert_eq!(square(3), 9);
+        assert_eq!(square(4), 16);
+        assert_eq!(CALLS.get(), 2);
+
+        new_scope();
+        assert_eq!(square(3), 9);
+        assert_eq!(CALLS.get(), 3);
+    }
+
+    #[test]
+    fn key_projections_decide_what_is_cached() {
+        new_scope();
+        assert_eq!(weighted_sum(0, &[1, 2]), 3);
+        // Only the index is part of the key, so this returns the result cached above.
+        assert_eq!(weighted_sum(0, &[5]), 3);
+        assert_eq!(weighted_sum(1, &[5]), 10);
+    }
+
+    #[test]
+    fn cache_is_dropped_in_a_new_scope() {
+        let mut cache = MemoCache::new();
+        cache.insert(1, "one");
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.len(), 1);
+
+        new_scope();
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn inputs_solved_in_a_row_do_not_share_entries() {
+        assert_eq!(Sums::run_one("1 2\n3"), Answer::Number(9));
+        assert_eq!(Sums::run_one("10\n20 30"), Answer::Number(110));
+        assert_eq!(Sums::run("4\n5"), (Answer::Number(14), Answer::Number(0)));
+
+        let (day, data) = Sums::init("7 7");
+        assert_eq!(day.crosscheck(&data), [None, None]);
+        assert_eq!(day.solve(&mut data.clone(), Sums::one), Answer::Number(14));
+        assert_eq!(
+            Sums::test_one(1),
+            Some((true, Answer::Number(9), Answer::Number(9)))
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use crate::days::{Day, DayImpl};
+use aoc_macro::match_and_visualize_day;
+use colored::Color;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write as _};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The way a single cell of a [`Frame`] is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Self {
+        Self {
+            foreground: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn bg(color: Color) -> Self {
+        Self {
+            background: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// The ANSI escape sequence selecting this style.
+    fn to_ansi(self) -> String {
+        let mut codes = vec!["0".to_owned()];
+        if self.bold {
+            codes.push("1".to_owned());
+        }
+        if let Some(color) = self.foreground {
+            codes.push(color.to_fg_str().into_owned());
+        }
+        if let Some(color) = self.background {
+            codes.push(color.to_bg_str().into_owned());
+        }
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub symbol: char,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: ' ',
+            style: Style::default(),
+        }
+    }
+}
+
+/// A single step of a visualization: a grid of styled cells, and a caption describing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    cells: Vec<Vec<Cell>>,
+    caption: String,
+}
+
+impl Frame {
+    /// Creates an empty frame of the specified dimensions.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: vec![vec![Cell::default(); width]; height],
+            caption: String::new(),
+        }
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (
+            self.cells.first().map(|v| v.len()).unwrap_or(0),
+            self.cells.len(),
+        )
+    }
+
+    /// Sets a cell. Positions outside the frame are ignored.
+    pub fn set(&mut self, x: usize, y: usize, symbol: char, style: Style) {
+        if let Some(cell) = self.cells.get_mut(y).and_then(|line| line.get_mut(x)) {
+            *cell = Cell { symbol, style };
+        }
+    }
+
+    /// Renders the frame, including the caption, using ANSI escape sequences.
+    fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for line in &self.cells {
+            let mut current = None;
+            for cell in line {
+                if current != Some(cell.style) {
+                    out += &cell.style.to_ansi();
+                    current = Some(cell.style);
+                }
+                out.push(cell.symbol);
+            }
+            out += "\x1b[0m\r\n";
+        }
+        out + &self.caption + "\r\n"
+    }
+}
+
+/// How the frames of a visualization are output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VisualizationOutput {
+    /// Play the frames as an animation in the terminal.
+    Terminal,
+    /// Write an [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) recording to a file.
+    Asciicast(String),
+    /// Write every frame as a PPM image into a directory.
+    Images(String),
+}
+
+/// Escapes a string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\r' => out += "\\r",
+            '\t' => out += "\\t",
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out + "\""
+}
+
+/// An approximation of the usual terminal colors, used when rendering images.
+fn color_to_rgb(color: Color) -> [u8; 3] {
+    match color {
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 49, 49],
+        Color::Green => [13, 188, 121],
+        Color::Yellow => [229, 229, 16],
+        Color::Blue => [36, 114, 200],
+        Color::Magenta => [188, 63, 188],
+        Color::Cyan => [17, 168, 205],
+        Color::White => [229, 229, 229],
+        Color::BrightBlack => [102, 102, 102],
+        Color::BrightRed => [241, 76, 76],
+        Color::BrightGreen => [35, 209, 139],
+        Color::BrightYellow => [245, 245, 67],
+        Color::BrightBlue => [59, 142, 234],
+        Color::BrightMagenta => [214, 112, 214],
+        Color::BrightCyan => [41, 184, 219],
+        Color::BrightWhite => [255, 255, 255],
+        Color::TrueColor { r, g, b } => [r, g, b],
+    }
+}
+
+/// Renders a frame as a binary PPM image, drawing each cell as a square of `cell_size` pixels.
+///
+/// Cells are drawn in their background color, or in their foreground color if they only have a symbol.
+fn to_ppm(frame: &Frame, cell_size: usize) -> Vec<u8> {
+    let (width, height) = frame.dimensions();
+    let mut image = format!("P6\n{} {}\n255\n", width * cell_size, height * cell_size).into_bytes();
+
+    for line in &frame.cells {
+        let pixels: Vec<u8> = line
+            .iter()
+            .flat_map(|cell| {
+                let color = match (cell.style.background, cell.symbol) {
+                    (Some(color), _) => color_to_rgb(color),
+                    (None, ' ') => [0, 0, 0],
+                    (None, _) => color_to_rgb(cell.style.foreground.unwrap_or(Color::White)),
+                };
+                std::iter::repeat_n(color, cell_size).flatten()
+            })
+            .collect();
+        for _ in 0..cell_size {
+            image.extend_from_slice(&pixels);
+        }
+    }
+
+    image
+}
+
+/// Outputs the frames of a visualization one by one, as soon as they are emitted.
+///
+/// Files are only created once the first frame arrives.
+enum FrameSink {
+    /// Plays the frames as an animation, waiting `frame_time` after each one.
+    Terminal { frame_time: Duration },
+    /// Writes an asciicast recording. Its terminal size is taken from the first frame.
+    Asciicast {
+        path: PathBuf,
+        file: Option<BufWriter<File>>,
+        frame_time: Duration,
+        frames: usize,
+    },
+    /// Writes every frame as a PPM image, and their captions to `captions.txt`, one line per frame.
+    Images {
+        directory: PathBuf,
+        captions: Option<BufWriter<File>>,
+        frames: usize,
+    },
+}
+
+impl FrameSink {
+    fn new(output: VisualizationOutput, frame_time: Duration) -> Self {
+        match output {
+            VisualizationOutput::Terminal => Self::Terminal { frame_time },
+            VisualizationOutput::Asciicast(path) => Self::Asciicast {
+                path: path.into(),
+                file: None,
+                frame_time,
+                frames: 0,
+            },
+            VisualizationOutput::Images(directory) => Self::Images {
+                directory: directory.into(),
+                captions: None,
+                frames: 0,
+            },
+        }
+    }
+
+    fn write(&mut self, frame: &Frame) -> std::io::Result<()> {
+        match self {
+            Self::Terminal { frame_time } => {
+                let mut stdout = std::io::stdout();
+                // clear the screen and move the cursor to the top left.
+                write!(stdout, "\x1b[2J\x1b[H{}", frame.to_ansi())?;
+                stdout.flush()?;
+                thread::sleep(*frame_time);
+            }
+            Self::Asciicast {
+                path,
+                file,
+                frame_time,
+                frames,
+            } => {
+                let file = match file {
+                    Some(file) => file,
+                    None => {
+                        let (width, height) = frame.dimensions();
+                        let timestamp = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .map(|v| v.as_secs())
+                            .unwrap_or(0);
+
+                        let mut new_file = BufWriter::new(File::create(path.as_path())?);
+                        writeln!(
+                            new_file,
+                            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}}}",
+                            width.max(frame.caption.chars().count()),
+                            height + 1,
+                            timestamp
+                        )?;
+                        file.insert(new_file)
+                    }
+                };
+
+                let time = frame_time.as_secs_f64() * *frames as f64;
+                let data = format!("\x1b[2J\x1b[H{}", frame.to_ansi());
+                writeln!(file, "[{:.6}, \"o\", {}]", time, json_string(&data))?;
+                *frames += 1;
+            }
+            Self::Images {
+                directory,
+                captions,
+                frames,
+            } => {
+                let captions = match captions {
+                    Some(captions) => captions,
+                    None => {
+                        fs::create_dir_all(directory.as_path())?;
+                        captions.insert(BufWriter::new(File::create(
+                            directory.join("captions.txt"),
+                        )?))
+                    }
+                };
+
+                fs::write(
+                    directory.join(format!("frame_{:05}.ppm", frames)),
+                    to_ppm(frame, 8),
+                )?;
+                writeln!(captions, "{}", frame.caption)?;
+                *frames += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            Self::Terminal { .. } => Ok(()),
+            Self::Asciicast { file, .. } => file.map_or(Ok(()), |mut file| file.flush()),
+            Self::Images { captions, .. } => captions.map_or(Ok(()), |mut file| file.flush()),
+        }
+    }
+}
+
+/// Visualizes the solution of a day, using either the provided input or the example input.
+///
+/// Frames are output while the day is still solving, so long visualizations don't need to fit into memory.
+/// `fps` has to be positive and finite.
+///
+/// Returns false if the day doesn't support visualization.
+pub fn visualize_day(
+    day: u8,
+    input: Option<&str>,
+    output: VisualizationOutput,
+    fps: f64,
+) -> std::io::Result<bool> {
+    let frame_time = Duration::try_from_secs_f64(1.0 / fps)
+        .ok()
+        .filter(|_| fps.is_finite())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid frame rate {}", fps),
+            )
+        })?;
+
+    let input = input.map(|v| v.trim_end());
+    let mut sink = FrameSink::new(output, frame_time);
+    let mut result = Ok(());
+    let emit = &mut |frame: Frame| {
+        // After an error, the remaining frames are dropped.
+        if result.is_ok() {
+            result = sink.write(&frame);
+        }
+    };
+
+    let supported = match_and_visualize_day!();
+
+    result?;
+    sink.finish()?;
+    Ok(supported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_string_escapes_control_characters() {
+        assert_eq!(json_string("a\"b\\c\n\x1b"), "\"a\\\"b\\\\c\\n\\u001b\"");
+    }
+
+    #[test]
+    fn asciicast_is_written_frame_by_frame() {
+        let path = std::env::temp_dir().join(format!("aoc25_test_{}.cast", std::process::id()));
+        let mut sink = FrameSink::new(
+            VisualizationOutput::Asciicast(path.to_string_lossy().into_owned()),
+            Duration::from_millis(500),
+        );
+
+        let mut frame = Frame::new(3, 2).with_caption("first");
+        frame.set(1, 1, '#', Style::default());
+        sink.write(&frame).unwrap();
+        sink.write(&frame.clone().with_caption("second")).unwrap();
+        sink.finish().unwrap();
+
+        let cast = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 5, \"height\": 3, "));
+        assert!(lines[1].starts_with("[0.000000, \"o\", "));
+        assert!(lines[1].contains("first"));
+        assert!(lines[2].starts_with("[0.500000, \"o\", "));
+        assert!(lines[2].contains("second"));
+    }
+}